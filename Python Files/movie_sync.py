@@ -8,8 +8,10 @@ import numpy as np
 import soundfile as sf
 import librosa
 from scipy.signal import fftconvolve
+import json
 import os
 import subprocess
+import threading
 import argparse
 import glob
 from concurrent.futures import ThreadPoolExecutor, as_completed
@@ -25,13 +27,63 @@ from rich.progress import Progress, BarColumn, TextColumn, TimeElapsedColumn, Sp
 # Initialize Rich Console
 console = Console()
 
-def load_audio(path: str, sr: int, duration: Optional[float] = None, offset: float = 0, verbose: bool = False) -> Optional[np.ndarray]:
+# EBU R128 loudness target applied with `normalize`.
+LOUDNORM_FILTER = 'loudnorm=I=-23:TP=-2:LRA=7:print_format=json'
+# Channel mixes for `downmix`; "mono" is FFmpeg's own -ac 1 downmix.
+DOWNMIX_FILTERS = {
+    'stereo': 'pan=mono|c0=0.5*FL+0.5*FR',
+    'center_only': 'pan=mono|c0=FC',
+}
+
+# Keys of FFmpeg's `-progress` output; every other stderr line is a message.
+PROGRESS_KEYS = {'frame', 'fps', 'bitrate', 'total_size', 'out_time_us', 'out_time_ms', 'out_time',
+                 'dup_frames', 'drop_frames', 'speed', 'progress'}
+
+
+def run_ffmpeg(cmd: List[str], duration: Optional[float] = None,
+               progress: Optional[Callable[[float], None]] = None) -> Tuple[int, bytes, bytes]:
+    """Runs FFmpeg, returning its exit code, stdout and stderr. With a
+    `progress` callback and a known output `duration`, reports the share of it
+    decoded so far from FFmpeg's `-progress` output as it goes."""
+    if progress is None or not duration:
+        process = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE)
+        stdout, stderr = process.communicate()
+        return process.returncode, stdout, stderr
+
+    cmd = cmd[:1] + ['-progress', 'pipe:2', '-nostats'] + cmd[1:]
+    process = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE)
+    messages = []
+
+    def read_stderr():
+        for line in process.stderr:
+            key, _, value = line.decode('utf-8', errors='ignore').strip().partition('=')
+            # out_time_ms is in microseconds too; older FFmpeg only sends it.
+            if key in ('out_time_us', 'out_time_ms') and value.isdigit():
+                progress(min(int(value) / 1e6 / duration, 1.0))
+            elif key not in PROGRESS_KEYS and not key.startswith('stream_'):
+                messages.append(line)
+
+    reader = threading.Thread(target=read_stderr, daemon=True)
+    reader.start()
+    stdout = process.stdout.read()
+    process.wait()
+    reader.join()
+    return process.returncode, stdout, b''.join(messages)
+
+
+def load_audio(
+    path: str, sr: int, duration: Optional[float] = None, offset: float = 0, verbose: bool = False,
+    stream: Optional[int] = None, normalize: bool = False, downmix: Optional[str] = None,
+    progress: Optional[Callable[[float], None]] = None
+) -> Optional[np.ndarray]:
     """Loads audio from a file, handling video extraction via in-memory pipe."""
     video_exts = ['.mp4', '.mkv', '.webm', '.avi', '.mov', '.eac3', '.ac3']
     _, ext = os.path.splitext(path)
     is_video = ext.lower() in video_exts
 
-    if not is_video:
+    # A specific stream, loudness normalization or a channel mix needs FFmpeg.
+    filters = [f for f in (DOWNMIX_FILTERS.get(downmix), LOUDNORM_FILTER if normalize else None) if f]
+    if not is_video and stream is None and not filters:
         try:
             with sf.SoundFile(path, 'r') as f:
                 seek_frame = int(offset * f.samplerate)
@@ -56,17 +108,20 @@ def load_audio(path: str, sr: int, duration: Optional[float] = None, offset: flo
         if offset > 0:
             cmd.extend(['-ss', str(offset)])
         cmd.extend(['-i', path])
+        if stream is not None:
+            cmd.extend(['-map', f'0:{stream}'])
         if duration is not None:
             cmd.extend(['-t', str(duration)])
+        if filters:
+            cmd.extend(['-af', ','.join(filters)])
         cmd.extend([
             '-vn', '-f', 's16le', '-acodec', 'pcm_s16le',
             '-ar', str(sr), '-ac', '1', '-'
         ])
 
-        process = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE)
-        stdout, stderr = process.communicate()
+        returncode, stdout, stderr = run_ffmpeg(cmd, duration, progress)
 
-        if process.returncode != 0:
+        if returncode != 0:
             if b"video stream" in stderr.lower() or b"doesn't contain any video stream" in stderr.lower():
                  y, _ = librosa.load(path, sr=sr, mono=True, duration=duration, offset=offset)
                  return y
@@ -75,6 +130,8 @@ def load_audio(path: str, sr: int, duration: Optional[float] = None, offset: flo
                 console.print(f"[dim]{stderr.decode('utf-8', errors='ignore')}[/dim]")
             return None
 
+        if normalize:
+            _record_loudness(path, stderr)
         return np.frombuffer(stdout, dtype=np.int16).astype(np.float32) / 32768.0
 
     except FileNotFoundError:
@@ -127,8 +184,69 @@ def get_audio_duration(path: str, verbose: bool = False) -> Optional[float]:
         if verbose: console.print(f"[dim]Librosa error: {e}[/dim]")
         return None
 
+# Correlation peaks recorded by estimate_sync_offset_crosscorr on this thread,
+# between begin_confidence_capture and end_confidence_capture.
+_capture = threading.local()
+
+def begin_confidence_capture() -> None:
+    _capture.peaks = []
+
+def end_confidence_capture() -> Optional[float]:
+    """Weakest correlation peak (0-1) since begin_confidence_capture, if any."""
+    peaks = getattr(_capture, 'peaks', None)
+    _capture.peaks = None
+    return min(peaks) if peaks else None
+
+def begin_loudness_capture() -> None:
+    _capture.loudness = {}
+
+def end_loudness_capture() -> dict:
+    """Integrated loudness (LUFS) loudnorm measured since
+    begin_loudness_capture, by path; the first window loaded of each."""
+    loudness = getattr(_capture, 'loudness', None) or {}
+    _capture.loudness = None
+    return loudness
+
+def _record_loudness(path: str, stderr: bytes) -> None:
+    loudness = getattr(_capture, 'loudness', None)
+    if loudness is None or path in loudness:
+        return
+    # With print_format=json, loudnorm ends its output with a JSON summary.
+    text = stderr.decode('utf-8', errors='ignore')
+    start, end = text.rfind('{'), text.rfind('}')
+    if start < 0 or end < start:
+        return
+    try:
+        measured = float(json.loads(text[start:end + 1])['input_i'])
+    except (ValueError, KeyError, TypeError):
+        return
+    if np.isfinite(measured):
+        loudness[path] = measured
+
+def _record_confidence(peak: float, length: int) -> None:
+    peaks = getattr(_capture, 'peaks', None)
+    if peaks is None or length == 0:
+        return
+    # Both inputs are standardized, so the peak over the overlap is a
+    # correlation coefficient.
+    peaks.append(float(min(1.0, max(0.0, peak / length))))
+
+def _peak_lag(corr: np.ndarray, zero: int, step_ms: float,
+              lag_range: Optional[Tuple[float, float]] = None) -> int:
+    """Offset from `zero` (the zero-lag index) of corr's highest point, with
+    the search limited to lags within lag_range (ms) when given."""
+    lo, hi = 0, len(corr)
+    if lag_range is not None:
+        lo = max(lo, zero + int(np.ceil(lag_range[0] / step_ms)))
+        hi = min(hi, zero + int(np.floor(lag_range[1] / step_ms)) + 1)
+        if lo >= hi:
+            raise ValueError("No delay within the max offset fits the measured windows.")
+    peak = lo + int(np.argmax(corr[lo:hi]))
+    return peak - zero
+
 def estimate_sync_offset_crosscorr(
-    primary_audio: np.ndarray, secondary_audio: np.ndarray, sr: int
+    primary_audio: np.ndarray, secondary_audio: np.ndarray, sr: int,
+    lag_range: Optional[Tuple[float, float]] = None
 ) -> float:
     """Estimate delay (in ms) using fast FFT cross-correlation."""
     def _normalize(y: np.ndarray) -> np.ndarray:
@@ -142,33 +260,92 @@ def estimate_sync_offset_crosscorr(
     y_s = _normalize(secondary_audio)
 
     corr = fftconvolve(y_p, y_s[::-1], mode='full')
-    lag = np.argmax(corr) - (len(y_s) - 1)
+    lag = _peak_lag(corr, len(y_s) - 1, 1000 / sr, lag_range)
+    _record_confidence(corr[lag + len(y_s) - 1], min(len(y_p), len(y_s)))
     delay_sec = lag / sr
     return delay_sec * 1000
 
+# Samples per onset-envelope frame for the fingerprint algorithm.
+FINGERPRINT_HOP = 64
+
+def estimate_sync_offset_fingerprint(
+    primary_audio: np.ndarray, secondary_audio: np.ndarray, sr: int,
+    lag_range: Optional[Tuple[float, float]] = None
+) -> float:
+    """Estimate delay (in ms) by correlating onset-strength envelopes.
+
+    Tolerates different mixes, EQ and codecs better than the raw waveform,
+    at a resolution of FINGERPRINT_HOP samples.
+    """
+    def _envelope(y: np.ndarray) -> np.ndarray:
+        env = librosa.onset.onset_strength(y=y.astype(np.float32), sr=sr, hop_length=FINGERPRINT_HOP)
+        env = env - np.mean(env)
+        std = np.std(env)
+        if std > 1e-8:
+            env /= std
+        return env
+
+    env_p = _envelope(primary_audio)
+    env_s = _envelope(secondary_audio)
+
+    corr = fftconvolve(env_p, env_s[::-1], mode='full')
+    lag = _peak_lag(corr, len(env_s) - 1, FINGERPRINT_HOP / sr * 1000, lag_range)
+    _record_confidence(corr[lag + len(env_s) - 1], min(len(env_p), len(env_s)))
+    return lag * FINGERPRINT_HOP / sr * 1000
+
+# Delay estimators by the name requests pick them with.
+ALGORITHMS = {
+    'crosscorr': estimate_sync_offset_crosscorr,
+    'fingerprint': estimate_sync_offset_fingerprint,
+}
+
+def estimate_sync_offset(
+    primary_audio: np.ndarray, secondary_audio: np.ndarray, sr: int, algorithm: Optional[str] = None,
+    lag_range: Optional[Tuple[float, float]] = None
+) -> float:
+    """Estimate delay (in ms) with one of ALGORITHMS, crosscorr by default,
+    only considering delays within lag_range (ms) when given."""
+    return ALGORITHMS[algorithm or 'crosscorr'](primary_audio, secondary_audio, sr=sr, lag_range=lag_range)
+
+def offset_range(max_offset_ms: Optional[float], shift_ms: float = 0.0) -> Optional[Tuple[float, float]]:
+    """Raw lags (ms) that keep `raw + shift_ms` within max_offset_ms either
+    way, or None for no limit."""
+    if max_offset_ms is None:
+        return None
+    return (-max_offset_ms - shift_ms, max_offset_ms - shift_ms)
+
 def process_pair(
     video_path: str, audio_path: str, segment_sec: float, verbose: bool = False,
-    progress_callback: Optional[Callable[[int], None]] = None
+    progress_callback: Optional[Callable[[int], None]] = None, sample_rate: int = 8000,
+    video_stream: Optional[int] = None, audio_stream: Optional[int] = None, normalize: bool = False,
+    downmix: Optional[str] = None, algorithm: Optional[str] = None, max_offset_ms: Optional[float] = None
 ) -> Tuple[str, str, Optional[float], Optional[float], Optional[str]]:
     """Processes a single video file against the audio file."""
-    fast_sr = 8000
+    fast_sr = sample_rate
     start_delay: Optional[float] = None
     end_delay: Optional[float] = None
 
+    def stage(base):
+        """Maps one of the four loads' progress onto 25 points from `base`."""
+        if progress_callback is None:
+            return None
+        return lambda fraction: progress_callback(base + int(fraction * 25))
+
     try:
         # Start analysis
-        video_audio_start = load_audio(video_path, sr=fast_sr, duration=segment_sec, verbose=verbose)
+        video_audio_start = load_audio(video_path, sr=fast_sr, duration=segment_sec, verbose=verbose, stream=video_stream, normalize=normalize, downmix=downmix, progress=stage(0))
         if video_audio_start is None:
             return video_path, audio_path, None, None, f"Failed to load start of video: {os.path.basename(video_path)}"
 
-        secondary_audio_start = load_audio(audio_path, sr=fast_sr, duration=segment_sec, verbose=verbose)
+        secondary_audio_start = load_audio(audio_path, sr=fast_sr, duration=segment_sec, verbose=verbose, stream=audio_stream, normalize=normalize, downmix=downmix, progress=stage(25))
         if secondary_audio_start is None:
             return video_path, audio_path, None, None, f"Failed to load start of audio: {os.path.basename(audio_path)}"
 
         min_len_start = min(len(video_audio_start), len(secondary_audio_start))
         if min_len_start > fast_sr:
-            start_delay = estimate_sync_offset_crosscorr(
-                video_audio_start[:min_len_start], secondary_audio_start[:min_len_start], sr=fast_sr
+            start_delay = estimate_sync_offset(
+                video_audio_start[:min_len_start], secondary_audio_start[:min_len_start], sr=fast_sr, algorithm=algorithm,
+                lag_range=offset_range(max_offset_ms)
             )
             if progress_callback:
                 progress_callback(50)
@@ -185,21 +362,22 @@ def process_pair(
         video_offset = max(0, video_duration - segment_sec)
         audio_offset = max(0, audio_duration - segment_sec)
 
-        video_audio_end = load_audio(video_path, sr=fast_sr, duration=segment_sec, offset=video_offset, verbose=verbose)
+        video_audio_end = load_audio(video_path, sr=fast_sr, duration=segment_sec, offset=video_offset, verbose=verbose, stream=video_stream, normalize=normalize, downmix=downmix, progress=stage(50))
         if video_audio_end is None:
             return video_path, audio_path, start_delay, None, f"Failed to load end of video: {os.path.basename(video_path)}"
 
-        secondary_audio_end = load_audio(audio_path, sr=fast_sr, duration=segment_sec, offset=audio_offset, verbose=verbose)
+        secondary_audio_end = load_audio(audio_path, sr=fast_sr, duration=segment_sec, offset=audio_offset, verbose=verbose, stream=audio_stream, normalize=normalize, downmix=downmix, progress=stage(75))
         if secondary_audio_end is None:
             return video_path, audio_path, start_delay, None, f"Failed to load end of audio: {os.path.basename(audio_path)}"
 
         min_len_end = min(len(video_audio_end), len(secondary_audio_end))
 
         if min_len_end > fast_sr:
-            end_delay_raw = estimate_sync_offset_crosscorr(
-                video_audio_end[:min_len_end], secondary_audio_end[:min_len_end], sr=fast_sr
-            )
             duration_diff_ms = (video_duration - audio_duration) * 1000
+            end_delay_raw = estimate_sync_offset(
+                video_audio_end[:min_len_end], secondary_audio_end[:min_len_end], sr=fast_sr, algorithm=algorithm,
+                lag_range=offset_range(max_offset_ms, duration_diff_ms)
+            )
             end_delay = end_delay_raw + duration_diff_ms
         else:
             return video_path, audio_path, start_delay, None, "Insufficient audio at end for analysis."
@@ -1,3 +1,4 @@
+import fnmatch
 import json
 import os
 import sys
@@ -23,7 +24,25 @@ except Exception as exc:
     sys.stderr.write(f"Failed to import Python scripts: {exc}\n")
     sys.exit(1)
 
+BRIDGE_VERSION = "1.7.0"
+# Bumped whenever the request or message format changes incompatibly.
+PROTOCOL_VERSION = 1
+# Request options this bridge understands, announced in the "hello" message.
+CAPABILITIES = ["pairs", "per_file_options", "sample_rate", "video_audio_stream", "audio_stream", "anchor_points",
+                "normalize", "offset_hint", "selftest", "downmix", "algorithm", "max_offset"]
+DEFAULT_SAMPLE_RATE = 8000
+# Delay estimator used when the request names none; the others are listed
+# in the "hello" message's "algorithms".
+DEFAULT_ALGORITHM = "crosscorr"
+# Integrated loudness (LUFS) the sync modules' LOUDNORM_FILTER aims for.
+LOUDNORM_TARGET = -23.0
+# Delay the self-test puts between its synthetic pair, and how far off the
+# measurement may be.
+SELF_TEST_DELAY_MS = 250
+SELF_TEST_TOLERANCE_MS = 5
+
 _emit_lock = threading.Lock()
+_cancel_event = threading.Event()
 
 
 def emit(payload):
@@ -36,52 +55,277 @@ def emit_log(message):
     emit({"type": "log", "message": message})
 
 
+def listen_for_control():
+    """Reads control messages sent after the request line (e.g. cancel)."""
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            message = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        if message.get("type") == "cancel":
+            emit_log("Cancel requested; finishing in-flight files.")
+            _cancel_event.set()
+
+
 def normalize_result(result_tuple):
     primary_path, secondary_path, start_delay, end_delay, error = result_tuple
     return {
         "videoFile": os.path.basename(primary_path),
         "audioFile": os.path.basename(secondary_path),
+        "videoPath": primary_path,
+        "audioPath": secondary_path,
         "startDelay": start_delay,
         "endDelay": end_delay,
         "error": error,
     }
 
 
-def list_movie_videos(video_folder, explicit_files):
+def video_folders_of(request):
+    folders = request.get("video_folders") or []
+    if not folders and request.get("video_folder"):
+        folders = [request.get("video_folder")]
+    return folders
+
+
+def list_movie_videos(video_folders, explicit_files):
     if explicit_files:
         return explicit_files
     exts = ("*.mp4", "*.mkv", "*.webm", "*.avi", "*.mov")
-    return [f for ext in exts for f in glob.glob(os.path.join(video_folder, ext))]
+    return [f for folder in video_folders for ext in exts for f in glob.glob(os.path.join(folder, ext))]
+
+
+def video_name_allowed(request, video_path):
+    """Applies the request's include/exclude globs to a video file name."""
+    name = os.path.basename(video_path)
+    include = request.get("include_glob")
+    exclude = request.get("exclude_glob")
+    if include and not fnmatch.fnmatchcase(name, include):
+        return False
+    if exclude and fnmatch.fnmatchcase(name, exclude):
+        return False
+    return True
+
+
+def segment_for(request, video_path, default):
+    """Segment duration for one video, honouring per_file_options overrides."""
+    options = (request.get("per_file_options") or {}).get(video_path) or {}
+    value = options.get("segment_duration")
+    return float(value) if value is not None else default
+
+
+def stream_for(request, video_path, key):
+    """Stream index `key` picks for one pair, or None for the first audio stream."""
+    options = (request.get("per_file_options") or {}).get(video_path) or {}
+    value = options.get(key)
+    if value is None:
+        value = request.get(key)
+    return int(value) if value is not None else None
+
+
+def offset_hint_for(request, video_path):
+    """Rough delay in seconds to center the search on, or None for zero."""
+    options = (request.get("per_file_options") or {}).get(video_path) or {}
+    value = options.get("offset_hint_seconds")
+    if value is None:
+        value = request.get("offset_hint_seconds")
+    return float(value) if value is not None else None
+
+
+def max_offset_ms_of(request):
+    """Largest delay (ms) either way the search may find, or None for no limit."""
+    value = request.get("max_offset_seconds")
+    if value is None or float(value) < 0:
+        return None
+    return float(value) * 1000
+
+
+def downmix_for(request, video_path):
+    """Channel mix for one pair (see the sync modules' DOWNMIX_FILTERS), or None."""
+    options = (request.get("per_file_options") or {}).get(video_path) or {}
+    return options.get("downmix") or request.get("downmix")
+
+
+def video_stream_for(request, video_path):
+    return stream_for(request, video_path, "video_audio_stream")
+
+
+def audio_stream_for(request, video_path):
+    return stream_for(request, video_path, "audio_stream")
+
+
+def measure_anchors(logic, video_path, audio_path, segment, positions, sample_rate,
+                    video_stream=None, audio_stream=None, normalize=False, downmix=None, hint=None,
+                    algorithm=None, max_offset_ms=None, progress_callback=None):
+    """Measures the delay at each position, a fraction of the runtime.
+
+    Position 0 matches process_pair's start measurement and 1 its end one.
+    A hint (seconds) offsets the audio window so the search centers on it;
+    max_offset_ms bounds the delays the search may return.
+    Returns process_pair's tuple, with the start/end delays taken from the
+    first/last anchors, and the list of {position, delay_ms} entries.
+    """
+    video_duration = logic.get_audio_duration(video_path)
+    audio_duration = logic.get_audio_duration(audio_path)
+    if video_duration is None or audio_duration is None:
+        return (video_path, audio_path, None, None, "Could not get duration for anchor analysis."), []
+
+    def load_progress(index, load):
+        """Maps one load's progress onto its share of the anchors' range."""
+        if progress_callback is None:
+            return None
+        share = 100 / len(positions) / 2
+        return lambda fraction: progress_callback(int((index * 2 + load + fraction) * share))
+
+    delays = []
+    error = None
+    for done, position in enumerate(positions, start=1):
+        video_offset = max(0.0, position * (video_duration - segment))
+        audio_offset = max(0.0, position * (audio_duration - segment))
+        if hint:
+            audio_offset = video_offset - hint
+            if audio_offset < 0:
+                video_offset -= audio_offset
+                audio_offset = 0.0
+        delay = None
+        try:
+            video_audio = logic.load_audio(
+                video_path, sr=sample_rate, duration=segment, offset=video_offset, stream=video_stream,
+                normalize=normalize, downmix=downmix, progress=load_progress(done - 1, 0)
+            )
+            secondary_audio = logic.load_audio(
+                audio_path, sr=sample_rate, duration=segment, offset=audio_offset, stream=audio_stream,
+                normalize=normalize, downmix=downmix, progress=load_progress(done - 1, 1)
+            )
+            if video_audio is None or secondary_audio is None:
+                error = error or f"Failed to load audio at {position:.0%} of the runtime."
+            else:
+                length = min(len(video_audio), len(secondary_audio))
+                if length > sample_rate:
+                    # Windows start at different times when the runtimes differ.
+                    shift_ms = (video_offset - audio_offset) * 1000
+                    raw = logic.estimate_sync_offset(
+                        video_audio[:length], secondary_audio[:length], sr=sample_rate, algorithm=algorithm,
+                        lag_range=logic.offset_range(max_offset_ms, shift_ms)
+                    )
+                    delay = raw + shift_ms
+                else:
+                    error = error or f"Insufficient audio at {position:.0%} of the runtime for analysis."
+        except Exception as exc:
+            error = error or str(exc)
+        delays.append({"position": position, "delay_ms": delay})
+        if progress_callback:
+            progress_callback(int(done * 100 / len(positions)))
+
+    return (video_path, audio_path, delays[0]["delay_ms"], delays[-1]["delay_ms"], error), delays
+
+
+def analyze_pair(logic, request, video_path, audio_path, segment, sample_rate, progress_callback):
+    """Runs one pair, returning its result tuple, anchor delays (None without
+    anchor_points), the confidence (0-1) of its weakest measurement and,
+    when normalizing, the integrated loudness (LUFS) loudnorm measured on the
+    audio."""
+    logic.begin_confidence_capture()
+    logic.begin_loudness_capture()
+    try:
+        result, delays = measure_pair(
+            logic, request, video_path, audio_path, segment, sample_rate, progress_callback
+        )
+    finally:
+        confidence = logic.end_confidence_capture()
+        loudness = logic.end_loudness_capture()
+    return result, delays, confidence, loudness.get(audio_path)
+
+
+def measure_pair(logic, request, video_path, audio_path, segment, sample_rate, progress_callback):
+    video_stream = video_stream_for(request, video_path)
+    audio_stream = audio_stream_for(request, video_path)
+    normalize = bool(request.get("normalize"))
+    downmix = downmix_for(request, video_path)
+    algorithm = request.get("algorithm")
+    positions = request.get("anchor_points")
+    hint = offset_hint_for(request, video_path)
+    max_offset_ms = max_offset_ms_of(request)
+    if positions:
+        return measure_anchors(
+            logic, video_path, audio_path, segment, sorted(float(p) for p in positions), sample_rate,
+            video_stream=video_stream, audio_stream=audio_stream, normalize=normalize, downmix=downmix, hint=hint,
+            algorithm=algorithm, max_offset_ms=max_offset_ms,
+            progress_callback=progress_callback
+        )
+    if hint:
+        # process_pair always lines the windows up; measure start and end here instead.
+        result, _ = measure_anchors(
+            logic, video_path, audio_path, segment, [0.0, 1.0], sample_rate,
+            video_stream=video_stream, audio_stream=audio_stream, normalize=normalize, downmix=downmix, hint=hint,
+            algorithm=algorithm, max_offset_ms=max_offset_ms,
+            progress_callback=progress_callback
+        )
+        return result, None
+    result = logic.process_pair(
+        video_path, audio_path, segment, False,
+        progress_callback=progress_callback, sample_rate=sample_rate,
+        video_stream=video_stream, audio_stream=audio_stream, normalize=normalize, downmix=downmix,
+        algorithm=algorithm, max_offset_ms=max_offset_ms
+    )
+    return result, None
+
+
+def sample_rate_of(request):
+    return int(request.get("sample_rate") or DEFAULT_SAMPLE_RATE)
+
+
+def audio_files_of(request):
+    audio_file = request.get("audio_file")
+    if isinstance(audio_file, str):
+        return [audio_file]
+    return audio_file or []
 
 
 def run_movie(request):
-    video_folder = request.get("video_folder")
-    audio_file = request.get("audio_file")
+    video_folders = video_folders_of(request)
+    audio_files = audio_files_of(request)
     explicit_files = request.get("video_files") or []
+    explicit_pairs = request.get("pairs")
     segment = float(request.get("segment_duration", 300.0))
 
-    if not audio_file:
-        emit({"type": "done", "results": []})
-        return
-
-    if not video_folder and not explicit_files:
-        emit({"type": "done", "results": []})
-        return
-
-    video_files = list_movie_videos(video_folder, explicit_files)
-    total = len(video_files)
+    if explicit_pairs is not None:
+        jobs = [tuple(pair) for pair in explicit_pairs]
+    else:
+        if not audio_files:
+            emit({"type": "done", "results": []})
+            return
+
+        if not video_folders and not explicit_files:
+            emit({"type": "done", "results": []})
+            return
+
+        video_files = [
+            video_path
+            for video_path in list_movie_videos(video_folders, explicit_files)
+            if video_name_allowed(request, video_path)
+        ]
+        jobs = [(video_path, audio_path) for video_path in video_files for audio_path in audio_files]
+
+    total = len(jobs)
     results = []
 
     if total == 0:
         emit({"type": "done", "results": []})
         return
 
-    emit_log(f"Movie mode: {total} video files queued.")
-    emit_log(f"Audio file: {os.path.basename(audio_file)}")
+    emit_log(f"Movie mode: {total} video/audio pairs queued.")
+    for audio_path in sorted({audio for _, audio in jobs}):
+        emit_log(f"Audio file: {os.path.basename(audio_path)}")
 
+    sample_rate = sample_rate_of(request)
     processed = 0
     with ThreadPoolExecutor() as executor:
-        def worker(video_path):
+        def worker(video_path, audio_file):
+            if _cancel_event.is_set():
+                return None, 0, None, None, None
             emit({"type": "file_start", "file": os.path.basename(video_path)})
             emit({"type": "file_progress", "file": os.path.basename(video_path), "percent": 0})
             
@@ -89,21 +333,35 @@ def run_movie(request):
                 emit({"type": "file_progress", "file": os.path.basename(video_path), "percent": percent})
 
             start_time = time.time()
-            result = movie_logic.process_pair(
-                video_path, audio_file, segment, False,
-                progress_callback=progress_callback
+            result, delays, confidence, input_lufs = analyze_pair(
+                movie_logic, request, video_path, audio_file,
+                segment_for(request, video_path, segment), sample_rate, progress_callback
             )
             elapsed_ms = int((time.time() - start_time) * 1000)
             emit({"type": "file_progress", "file": os.path.basename(video_path), "percent": 100})
             emit({"type": "file_end", "file": os.path.basename(video_path), "elapsed_ms": elapsed_ms})
-            return result, elapsed_ms
+            return result, elapsed_ms, delays, input_lufs, confidence
 
-        futures = {executor.submit(worker, video_path): video_path for video_path in video_files}
+        futures = {executor.submit(worker, v, a): v for v, a in jobs}
         for future in as_completed(futures):
+            result, elapsed_ms, delays, input_lufs, confidence = future.result()
+            if result is None:
+                continue
             processed += 1
-            result, elapsed_ms = future.result()
             normalized = normalize_result(result)
             normalized["elapsed_ms"] = elapsed_ms
+            normalized["sample_rate"] = sample_rate
+            if delays is not None:
+                normalized["delays"] = delays
+            if input_lufs is not None:
+                normalized["input_lufs"] = round(input_lufs, 2)
+                # loudnorm is dynamic; this is the level shift it aimed for.
+                normalized["gain_db"] = round(LOUDNORM_TARGET - input_lufs, 2)
+            if confidence is not None:
+                normalized["confidence"] = round(confidence, 3)
+            normalized["algorithm"] = request.get("algorithm") or DEFAULT_ALGORITHM
+            normalized["video_stream"] = video_stream_for(request, futures[future])
+            normalized["audio_stream"] = audio_stream_for(request, futures[future])
             results.append(normalized)
             emit(
                 {
@@ -119,16 +377,24 @@ def run_movie(request):
 
 
 def run_series(request):
-    video_folder = request.get("video_folder")
+    video_folders = video_folders_of(request)
     audio_folder = request.get("audio_folder")
     match_pattern = request.get("match_pattern")
     segment = float(request.get("segment_duration", 300.0))
 
-    if not video_folder or not audio_folder:
+    explicit_pairs = request.get("pairs")
+    if explicit_pairs is not None:
+        matched_pairs = [tuple(pair) for pair in explicit_pairs]
+    elif not video_folders or not audio_folder:
         emit({"type": "done", "results": []})
         return
-
-    matched_pairs = series_logic.find_matching_files(video_folder, audio_folder, match_pattern, False)
+    else:
+        matched_pairs = [
+            pair
+            for video_folder in video_folders
+            for pair in series_logic.find_matching_files(video_folder, audio_folder, match_pattern, False)
+            if video_name_allowed(request, pair[0])
+        ]
     total = len(matched_pairs)
     results = []
 
@@ -138,14 +404,18 @@ def run_series(request):
         return
 
     emit_log(f"Series mode: matched {total} file pairs.")
-    emit_log(f"Video folder: {video_folder}")
+    for video_folder in video_folders:
+        emit_log(f"Video folder: {video_folder}")
     emit_log(f"Audio folder: {audio_folder}")
     if match_pattern:
         emit_log(f"Match pattern: {match_pattern}")
 
+    sample_rate = sample_rate_of(request)
     processed = 0
     with ThreadPoolExecutor() as executor:
         def worker(primary, secondary):
+            if _cancel_event.is_set():
+                return None, 0, None, None, None
             emit({"type": "file_start", "file": os.path.basename(primary)})
             emit({"type": "file_progress", "file": os.path.basename(primary), "percent": 0})
 
@@ -153,21 +423,35 @@ def run_series(request):
                 emit({"type": "file_progress", "file": os.path.basename(primary), "percent": percent})
 
             start_time = time.time()
-            result = series_logic.process_pair(
-                primary, secondary, segment, False,
-                progress_callback=progress_callback
+            result, delays, confidence, input_lufs = analyze_pair(
+                series_logic, request, primary, secondary,
+                segment_for(request, primary, segment), sample_rate, progress_callback
             )
             elapsed_ms = int((time.time() - start_time) * 1000)
             emit({"type": "file_progress", "file": os.path.basename(primary), "percent": 100})
             emit({"type": "file_end", "file": os.path.basename(primary), "elapsed_ms": elapsed_ms})
-            return result, elapsed_ms
+            return result, elapsed_ms, delays, input_lufs, confidence
 
         futures = {executor.submit(worker, p, s): (p, s) for p, s in matched_pairs}
         for future in as_completed(futures):
+            result, elapsed_ms, delays, input_lufs, confidence = future.result()
+            if result is None:
+                continue
             processed += 1
-            result, elapsed_ms = future.result()
             normalized = normalize_result(result)
             normalized["elapsed_ms"] = elapsed_ms
+            normalized["sample_rate"] = sample_rate
+            if delays is not None:
+                normalized["delays"] = delays
+            if input_lufs is not None:
+                normalized["input_lufs"] = round(input_lufs, 2)
+                # loudnorm is dynamic; this is the level shift it aimed for.
+                normalized["gain_db"] = round(LOUDNORM_TARGET - input_lufs, 2)
+            if confidence is not None:
+                normalized["confidence"] = round(confidence, 3)
+            normalized["algorithm"] = request.get("algorithm") or DEFAULT_ALGORITHM
+            normalized["video_stream"] = video_stream_for(request, futures[future][0])
+            normalized["audio_stream"] = audio_stream_for(request, futures[future][0])
             results.append(normalized)
             emit(
                 {
@@ -182,8 +466,83 @@ def run_series(request):
     emit({"type": "done", "results": results})
 
 
+def write_wav(path, samples, sample_rate):
+    import wave
+    import numpy as np
+
+    pcm = (np.clip(samples, -1.0, 1.0) * 32767).astype("<i2")
+    with wave.open(path, "wb") as handle:
+        handle.setnchannels(1)
+        handle.setsampwidth(2)
+        handle.setframerate(sample_rate)
+        handle.writeframes(pcm.tobytes())
+
+
+def run_self_test():
+    """Syncs a generated noise burst against a copy delayed by
+    SELF_TEST_DELAY_MS and muxed into Matroska, going through the same FFmpeg
+    decode and correlation as a real pair."""
+    import tempfile
+
+    stage = "generate"
+    try:
+        import numpy as np
+
+        sample_rate = DEFAULT_SAMPLE_RATE
+        shift = int(sample_rate * SELF_TEST_DELAY_MS / 1000)
+        noise = np.random.default_rng(0).uniform(-0.5, 0.5, sample_rate * 3)
+        delayed = np.concatenate([np.zeros(shift), noise[:-shift]])
+        with tempfile.TemporaryDirectory(prefix="audiosync-selftest-") as folder:
+            delayed_path = os.path.join(folder, "delayed.wav")
+            video_path = os.path.join(folder, "video.mkv")
+            audio_path = os.path.join(folder, "audio.wav")
+            write_wav(delayed_path, delayed, sample_rate)
+            write_wav(audio_path, noise, sample_rate)
+
+            # Real videos are decoded through FFmpeg, so the video side is a
+            # Matroska file that only FFmpeg can read.
+            stage = "ffmpeg"
+            try:
+                returncode, _, stderr = movie_logic.run_ffmpeg(
+                    ["ffmpeg", "-y", "-v", "error", "-i", delayed_path, "-c:a", "flac", video_path]
+                )
+            except FileNotFoundError:
+                raise RuntimeError("FFmpeg not found.")
+            if returncode != 0:
+                message = stderr.decode("utf-8", errors="ignore").strip()
+                raise RuntimeError(f"FFmpeg could not write the test video. {message}".strip())
+
+            stage = "decode"
+            video_audio = movie_logic.load_audio(video_path, sr=sample_rate)
+            secondary_audio = movie_logic.load_audio(audio_path, sr=sample_rate)
+            if video_audio is None or secondary_audio is None:
+                raise RuntimeError("Could not load the generated audio.")
+
+        stage = "correlate"
+        length = min(len(video_audio), len(secondary_audio))
+        delay = float(movie_logic.estimate_sync_offset_crosscorr(
+            video_audio[:length], secondary_audio[:length], sr=sample_rate
+        ))
+        if abs(delay - SELF_TEST_DELAY_MS) > SELF_TEST_TOLERANCE_MS:
+            raise RuntimeError(f"Measured {delay:.1f} ms, expected {SELF_TEST_DELAY_MS} ms.")
+    except Exception as exc:
+        emit({"type": "self_test", "ok": False, "stage": stage, "message": str(exc)})
+        return
+    emit({"type": "self_test", "ok": True, "stage": "done", "delay_ms": round(delay, 2)})
+
+
+def emit_hello():
+    emit({"type": "hello", "version": BRIDGE_VERSION, "protocol": PROTOCOL_VERSION, "capabilities": CAPABILITIES,
+          "algorithms": list(movie_logic.ALGORITHMS)})
+
+
 def main():
-    payload = sys.stdin.read()
+    if "--version" in sys.argv[1:]:
+        emit_hello()
+        return
+
+    # The request is the first line; the rest of stdin carries control messages.
+    payload = sys.stdin.readline()
     if not payload.strip():
         sys.stderr.write("No input provided.\n")
         sys.exit(1)
@@ -194,6 +553,12 @@ def main():
         sys.stderr.write(f"Invalid JSON input: {exc}\n")
         sys.exit(1)
 
+    emit_hello()
+    if request.get("type") == "selftest":
+        run_self_test()
+        return
+    threading.Thread(target=listen_for_control, daemon=True).start()
+
     mode = request.get("mode")
     if mode == "movie":
         run_movie(request)
@@ -0,0 +1,33 @@
+"""Stand-in bridge for checking that cancel and file timeouts kill the whole
+process tree.
+
+Run the app with AUDIOSYNC_BRIDGE pointing at this file and start any sync.
+The harness starts a sleeping grandchild, reports its pid in the log, and then
+ignores cancel requests, so the app has to kill it. After canceling, the pid
+from the log should no longer exist; if anything survives, the app logs it.
+"""
+import subprocess
+import sys
+
+# The handshake is the real bridge's, so requests it accepts reach the harness.
+from bridge import CAPABILITIES, PROTOCOL_VERSION, emit
+
+
+def main():
+    emit({"type": "hello", "version": "harness", "protocol": PROTOCOL_VERSION, "capabilities": CAPABILITIES,
+          "algorithms": ["crosscorr"]})
+    if "--version" in sys.argv[1:]:
+        return
+
+    sys.stdin.readline()
+    emit({"type": "file_start", "file": "harness.mkv"})
+    grandchild = subprocess.Popen([sys.executable, "-c", "import time; time.sleep(3600)"])
+    emit({"type": "log", "message": f"Harness grandchild started with pid {grandchild.pid}"})
+    # Swallow control messages, cancel included.
+    for _ in sys.stdin:
+        pass
+    grandchild.wait()
+
+
+if __name__ == "__main__":
+    main()
@@ -1,3 +1,8 @@
 fn main() {
+  // Lets the app look for sidecars named with the target triple.
+  println!(
+    "cargo:rustc-env=TARGET_TRIPLE={}",
+    std::env::var("TARGET").expect("cargo sets TARGET for build scripts")
+  );
   tauri_build::build()
 }
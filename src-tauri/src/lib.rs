@@ -1,12 +1,17 @@
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
-use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use tauri::{AppHandle, Emitter, Manager, State, Window};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Listener, Manager, State, Window};
 use tauri::path::BaseDirectory;
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_dialog::DialogExt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,35 +21,1172 @@ struct FileItem {
   #[serde(rename = "type")]
   file_type: String,
   size: Option<u64>,
+  duration: Option<f64>,
+  /// Last modification time in unix milliseconds.
+  modified: Option<u64>,
+  /// Creation time in unix milliseconds, where the platform records it.
+  created: Option<u64>,
+  /// Subtitle files next to a video sharing its stem, e.g. `movie.en.srt`;
+  /// only filled with `ListOptions.find_subtitles`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  subtitles: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 struct PickResponse {
   folder: Option<String>,
+  /// Every folder that contributed files; `folder` is their common ancestor.
+  #[serde(default)]
+  folders: Vec<String>,
   files: Vec<FileItem>,
+  /// Paths that couldn't be listed, e.g. from a drag-and-drop.
+  #[serde(default)]
+  errors: Vec<PathError>,
+  /// Picked paths that were kept but look unusable, e.g. no audio stream.
+  #[serde(default)]
+  warnings: Vec<PathError>,
+  /// Video files left out for being under `min_size_bytes`.
+  #[serde(default)]
+  skipped_small: usize,
+  /// Set when `files` is streamed through `pick-progress` events instead.
+  #[serde(default)]
+  listing_id: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PathError {
+  path: String,
+  message: String,
+}
+
+/// Error returned by commands, serialized as `{ "kind": ..., "message": ... }`
+/// so the UI can switch on `kind` instead of matching message text.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+enum AppError {
+  /// Neither the sidecar nor `bridge.py` could be found.
+  SidecarNotFound,
+  /// The bridge program was located but its executable (e.g. python) wasn't.
+  BridgeNotFound,
+  /// The user dismissed a dialog the command needed.
+  Canceled,
+  ProbeFailed(String),
+  InvalidRequest(String),
+  Io(String),
+  /// A `fail_fast` run stopped at `file`; `results` holds what had finished.
+  Aborted {
+    file: String,
+    error: String,
+    results: Vec<SyncResult>,
+  },
+  /// Anything without a more specific kind yet.
+  Other(String),
+}
+
+impl std::fmt::Display for AppError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      AppError::SidecarNotFound => {
+        f.write_str("Neither the audiosync-cli sidecar nor bridge.py was found. Build the sidecar into src-tauri/bin.")
+      }
+      AppError::BridgeNotFound => f.write_str("The bridge program could not be started: its executable was not found"),
+      AppError::Canceled => f.write_str("Canceled"),
+      AppError::ProbeFailed(message)
+      | AppError::InvalidRequest(message)
+      | AppError::Io(message)
+      | AppError::Other(message) => f.write_str(message),
+      AppError::Aborted { file, error, .. } => write!(f, "Stopped after {file} failed: {error}"),
+    }
+  }
+}
+
+impl From<std::io::Error> for AppError {
+  fn from(err: std::io::Error) -> Self {
+    AppError::Io(err.to_string())
+  }
+}
+
+const VIDEO_EXTENSIONS: [&str; 5] = ["mp4", "mkv", "webm", "avi", "mov"];
+
+const AUDIO_EXTENSIONS: [&str; 11] = [
+  "mka", "m4a", "aac", "ac3", "eac3", "dts", "flac", "opus", "ogg", "mp3", "wav",
+];
+
+/// A native dialog filter: display name and the extensions it admits.
+type DialogFilter = (&'static str, &'static [&'static str]);
+
+/// Escape hatch so unusual containers can still be picked.
+const ALL_FILES_FILTER: DialogFilter = ("All files", &["*"]);
+
+const SUBTITLE_EXTENSIONS: [&str; 3] = ["srt", "ass", "ssa"];
+
+const AUDIO_FILTERS: [DialogFilter; 2] = [("Audio", &AUDIO_EXTENSIONS), ALL_FILES_FILTER];
+
+const VIDEO_FILTERS: [DialogFilter; 1] = [("Video", &VIDEO_EXTENSIONS)];
+
+/// OS-generated files that never hold media.
+const JUNK_FILE_NAMES: [&str; 5] = [".ds_store", "thumbs.db", "desktop.ini", "ehthumbs.db", "icon\r"];
+
+/// Default number of subdirectory levels walked when recursive listing is enabled.
+const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Hard ceiling on recursion so a bad `max_depth` can't stall the picker.
+const MAX_DEPTH_LIMIT: usize = 16;
+
+/// Default for `ListOptions.network_timeout_secs`.
+const DEFAULT_NETWORK_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+struct ListOptions {
+  recursive: bool,
+  max_depth: Option<usize>,
+  /// Descend into symlinked directories; off by default to avoid cycles.
+  follow_symlinks: bool,
+  /// Run ffprobe over the listing to fill in `FileItem.duration`.
+  probe_durations: bool,
+  /// Keep dot-files, hidden-attribute files and OS junk like `Thumbs.db`.
+  include_hidden: bool,
+  /// Drop video files smaller than this; `0` disables the filter. Falls back
+  /// to the saved setting when omitted and is remembered when given.
+  min_size_bytes: Option<u64>,
+  /// Return right away and deliver files in `pick-progress` batches. Streamed
+  /// listings are neither probed nor sorted.
+  stream: bool,
+  /// Glob a file name must match to be listed, e.g. `*E0[1-5]*.mkv`.
+  include_glob: Option<String>,
+  /// Glob excluding matching file names.
+  exclude_glob: Option<String>,
+  /// Seconds to wait on a network share before reporting it unreachable.
+  network_timeout_secs: Option<u64>,
+  /// Fill `FileItem.subtitles` for videos, at the cost of reading each
+  /// video's folder once more.
+  find_subtitles: bool,
+  /// Extensions audio listings admit, case-insensitive and with or without
+  /// the dot; `AUDIO_EXTENSIONS` plus the video containers when unset.
+  audio_extensions: Option<Vec<String>>,
+}
+
+impl ListOptions {
+  /// Fills `min_size_bytes` from settings, or saves it there when provided.
+  fn with_saved_min_size(mut self, app: &AppHandle) -> Self {
+    match self.min_size_bytes {
+      Some(min_size) => {
+        if let Err(err) = update_settings(app, |settings| settings.min_size_bytes = min_size) {
+          log::warn!("Failed to remember minimum file size: {err}");
+        }
+      }
+      None => self.min_size_bytes = Some(load_settings_file(app).min_size_bytes),
+    }
+    self
+  }
+
+  /// Compiles the name globs, rejecting invalid patterns before any listing.
+  fn name_globs(&self) -> Result<NameGlobs, String> {
+    NameGlobs::new(self.include_glob.as_deref(), self.exclude_glob.as_deref())
+  }
+
+  /// Whether an audio listing should include `path`.
+  fn is_audio(&self, path: &Path) -> bool {
+    accepts_audio(path, self.audio_extensions.as_deref())
+  }
+
+  fn effective_depth(&self) -> usize {
+    if self.recursive {
+      self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH).min(MAX_DEPTH_LIMIT)
+    } else {
+      0
+    }
+  }
+}
+
+/// Include/exclude filters on file names (never the full path).
+#[derive(Debug, Default)]
+struct NameGlobs {
+  include: Option<glob::Pattern>,
+  exclude: Option<glob::Pattern>,
+}
+
+impl NameGlobs {
+  fn new(include: Option<&str>, exclude: Option<&str>) -> Result<Self, String> {
+    let compile = |kind: &str, pattern: Option<&str>| {
+      pattern
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+          glob::Pattern::new(&nfc(pattern)).map_err(|err| format!("Invalid {kind} glob '{pattern}': {err}"))
+        })
+        .transpose()
+    };
+    Ok(Self {
+      include: compile("include", include)?,
+      exclude: compile("exclude", exclude)?,
+    })
+  }
+
+  fn matches(&self, name: &str) -> bool {
+    self.include.as_ref().map_or(true, |pattern| pattern.matches(name))
+      && !self.exclude.as_ref().is_some_and(|pattern| pattern.matches(name))
+  }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum SortKey {
+  #[default]
+  Name,
+  Size,
+  Modified,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum SortOrder {
+  #[default]
+  Asc,
+  Desc,
+}
+
+/// Listing order requested by the UI, e.g. `{ "by": "size", "order": "desc" }`.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+struct SortOptions {
+  by: SortKey,
+  order: SortOrder,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct SyncRequest {
   mode: String,
   video_folder: Option<String>,
+  /// Additional video folders picked together; takes precedence over `video_folder`.
+  video_folders: Option<Vec<String>>,
   audio_folder: Option<String>,
-  audio_file: Option<String>,
+  /// Movie-mode audio files; accepts a single path or a list.
+  #[serde(default, deserialize_with = "string_or_list")]
+  audio_file: Option<Vec<String>>,
   video_files: Option<Vec<String>>,
   segment_duration: f64,
   match_pattern: Option<String>,
+  /// Seconds the bridge gets to wind down after a cancel before it is killed.
+  cancel_grace_secs: Option<u64>,
+  /// Number of bridge processes to run side by side, one file each. Also
+  /// accepted as `workers`.
+  #[serde(alias = "workers")]
+  concurrency: Option<usize>,
+  /// Explicit `(video, audio)` pairs; the bridge skips its own matching when set.
+  pairs: Option<Vec<(String, String)>>,
+  /// Seconds a file may go without start/progress messages before the bridge
+  /// is killed, the file recorded as timed out and a new bridge started for
+  /// the files left. A bridge that starts no file at all within it is
+  /// stopped the same way. `None` disables the check. Also accepted as
+  /// `file_timeout_seconds`.
+  #[serde(alias = "file_timeout_seconds")]
+  file_timeout_secs: Option<u64>,
+  /// Seconds of bridge silence before `sync-stalled` warns about it;
+  /// `DEFAULT_STALL_WARNING_SECS` when unset, `0` disables the warning.
+  stall_warning_secs: Option<u64>,
+  /// When the bridge last wrote anything, shared by the processes of a run.
+  #[serde(skip)]
+  heartbeat: Option<Arc<Heartbeat>>,
+  /// Name globs applied to video files found in the folders, as in listings.
+  include_glob: Option<String>,
+  exclude_glob: Option<String>,
+  /// Extensions admitted as audio when pairing from folders, as in
+  /// `ListOptions.audio_extensions`.
+  #[serde(default)]
+  audio_extensions: Option<Vec<String>>,
+  /// Longest bridge stdout line accepted; longer ones are skipped.
+  max_line_bytes: Option<usize>,
+  /// Series pairing strategy. `"season_episode"` pairs files by parsed
+  /// `S01E05`/`1x05` markers instead of the bridge's own matching;
+  /// `"fuzzy"` scores name similarity plus episode numbers; `"duration"`
+  /// pairs by probed runtime; `"metadata"` pairs by show/season/episode
+  /// tags, then by file name; `"pattern"` pairs files whose `match_pattern`
+  /// keys (see `pattern_key`) are equal.
+  match_strategy: Option<String>,
+  /// Fuzzy pairs scoring below this (0–100) need confirmation. Defaults to 70.
+  min_confidence: Option<u8>,
+  /// Results whose bridge-reported `correlation_confidence` (0–1) is below
+  /// this get `ResultStatus::LowConfidence` and a warning, but keep their
+  /// delays.
+  min_correlation_confidence: Option<f64>,
+  /// Largest believable delay either way. The bridge only searches delays
+  /// within it, and results still beyond it become errors. `None` is
+  /// unlimited.
+  max_offset_seconds: Option<f64>,
+  /// Rough delay in seconds for pairs without their own
+  /// `FileOptions.offset_hint_seconds`; unset searches around zero.
+  offset_hint_seconds: Option<f64>,
+  /// Largest runtime difference the `"duration"` strategy pairs. Defaults to 2.
+  duration_tolerance_secs: Option<f64>,
+  /// Sync fuzzy pairs that need confirmation instead of leaving them out.
+  #[serde(default)]
+  accept_low_confidence: bool,
+  /// Confidence of each entry in `pairs`, in the same order, when known.
+  pair_confidence: Option<Vec<u8>>,
+  /// Hand-picked audio for specific videos, applied over any matching.
+  pair_overrides: Option<Vec<PairOverride>>,
+  /// Series audio folders matched side by side, e.g. one per dub. Takes
+  /// precedence over `audio_folder`.
+  audio_sources: Option<Vec<AudioSource>>,
+  /// Per-video settings keyed by video path, overriding the request-wide ones.
+  per_file_options: Option<HashMap<String, FileOptions>>,
+  /// Analysis sample rate in Hz (8000–96000); the bridge's own default when unset.
+  sample_rate: Option<u32>,
+  /// ffprobe index of the video's audio stream to sync against; the first
+  /// audio stream when unset. Videos without it fail before the bridge runs.
+  video_audio_stream: Option<u32>,
+  /// ffprobe index of the audio stream to decode from the external audio
+  /// files; their first audio stream when unset.
+  audio_stream: Option<u32>,
+  /// Language tag (e.g. `jpn`) picking each video's audio stream when no
+  /// index is given; see `resolve_stream_languages`.
+  video_language: Option<String>,
+  /// Language tag picking the external audio files' stream, likewise.
+  audio_language: Option<String>,
+  /// Runtime fractions (0–1) to measure the delay at, reported as
+  /// `SyncResult.delays`; just the start and end when unset.
+  anchor_points: Option<Vec<f64>>,
+  /// Folder for synced outputs; next to each video when unset.
+  output_dir: Option<String>,
+  /// Output file name without extension (see `FILENAME_TOKENS`); defaults
+  /// to `DEFAULT_FILENAME_TEMPLATE`.
+  filename_template: Option<String>,
+  /// Run the bridge at below-normal priority; the settings' `low_priority`
+  /// when unset.
+  low_priority: Option<bool>,
+  /// Loudness-normalize both inputs before correlating; the settings'
+  /// `normalize` when unset. Results then carry the audio's measured
+  /// `input_lufs` and `gain_db`.
+  normalize: Option<bool>,
+  /// Delay estimator to use, one of the bridge's advertised `algorithms`;
+  /// unset uses its default cross-correlation.
+  algorithm: Option<String>,
+  /// How to mix multichannel audio down before correlating, one of
+  /// `DOWNMIX_MODES`; unset lets FFmpeg downmix to mono.
+  downmix: Option<String>,
+  /// Drift beyond this many ms per minute marks a result with
+  /// `ResultStatus::Warning`; `DEFAULT_MAX_DRIFT_MS_PER_MIN` when unset.
+  max_drift_ms_per_min: Option<f64>,
+  /// Only plan the run: match and check the inputs as usual, then emit
+  /// `sync-plan` and `ResultStatus::Planned` rows without spawning the bridge.
+  #[serde(default)]
+  dry_run: bool,
+  /// Skip pairs whose output (from `output_dir` and `filename_template`)
+  /// already exists and is newer than both inputs, emitting `sync-file-skip`
+  /// instead; see `skip_existing_pairs`.
+  #[serde(default)]
+  skip_existing: bool,
+  /// Sync every pair even when `skip_existing` is set.
+  #[serde(default)]
+  force: bool,
+  /// Skip pairs whose inputs lack the streams a sync needs instead of only
+  /// warning about them.
+  #[serde(default)]
+  strict: bool,
+  /// Stop the batch at the first result with an error, failing the run with
+  /// `AppError::Aborted`.
+  #[serde(default)]
+  fail_fast: bool,
+  /// Longer segment durations to retry a pair with, in order, when it fails
+  /// or comes back under `min_correlation_confidence`; see `retry_failed`.
+  retry_escalation: Option<Vec<f64>>,
+  /// Set on queued jobs (see `enqueue_sync`); their event payloads carry it
+  /// as `job_id`. `start_sync` takes one from the caller unless it's already
+  /// in use (see `SyncState::claim_job_id`).
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  job_id: Option<u64>,
+}
+
+/// Settings one video can override; unset fields fall back to the request's.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct FileOptions {
+  segment_duration: Option<f64>,
+  video_audio_stream: Option<u32>,
+  /// Stream of the audio file paired with this video.
+  audio_stream: Option<u32>,
+  /// Language to pick `video_audio_stream` by; once resolved, the picked
+  /// stream's own tag.
+  video_language: Option<String>,
+  /// Language to pick `audio_stream` by, likewise.
+  audio_language: Option<String>,
+  /// Rough delay in seconds, signed like the results' delays, for the bridge
+  /// to center its search on.
+  offset_hint_seconds: Option<f64>,
+  /// Channel mix to analyze with; once resolved, the one this pair's
+  /// channel layouts allow.
+  downmix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AudioSource {
+  folder: String,
+  /// Shown in results; defaults to the folder name.
+  label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PairOverride {
+  video: String,
+  audio: String,
+}
+
+const DEFAULT_CANCEL_GRACE_SECS: u64 = 3;
+
+/// Default for `SyncRequest.stall_warning_secs`.
+const DEFAULT_STALL_WARNING_SECS: u64 = 30;
+
+/// Default for `SyncRequest.min_confidence`.
+const DEFAULT_MIN_CONFIDENCE: u8 = 70;
+
+/// Default for `SyncRequest.max_drift_ms_per_min`.
+const DEFAULT_MAX_DRIFT_MS_PER_MIN: f64 = 1.0;
+
+/// Default for `SyncRequest.duration_tolerance_secs`.
+const DEFAULT_DURATION_TOLERANCE_SECS: f64 = 2.0;
+
+/// `payload` with `job_id` added when it's an object and `job_id` is set.
+fn tag_job(job_id: Option<u64>, payload: impl Serialize) -> serde_json::Value {
+  let mut value = serde_json::to_value(payload).unwrap_or_default();
+  if let (Some(id), Some(object)) = (job_id, value.as_object_mut()) {
+    object.insert("job_id".to_string(), id.into());
+  }
+  value
+}
+
+const SAMPLE_RATE_RANGE: std::ops::RangeInclusive<u32> = 8_000..=96_000;
+
+/// Values of `SyncRequest.downmix`: FFmpeg's mono downmix, the front
+/// left/right pair only, or the center (dialogue) channel only.
+const DOWNMIX_MODES: [&str; 3] = ["mono", "stereo", "center_only"];
+
+impl SyncRequest {
+  /// Rejects option values no run could honour.
+  fn validate(&self) -> Result<(), String> {
+    NameGlobs::new(self.include_glob.as_deref(), self.exclude_glob.as_deref())?;
+    let pattern = compile_match_pattern(self.match_pattern.as_deref())?;
+    if self.match_strategy.as_deref() == Some("pattern") && pattern.is_none() {
+      return Err("The pattern match strategy needs a match_pattern".to_string());
+    }
+    if let Some(rate) = self.sample_rate {
+      if !SAMPLE_RATE_RANGE.contains(&rate) {
+        return Err(format!(
+          "Sample rate {rate} Hz is outside {}–{} Hz",
+          SAMPLE_RATE_RANGE.start(),
+          SAMPLE_RATE_RANGE.end()
+        ));
+      }
+    }
+    if let Some(step) = self.retry_escalation.iter().flatten().find(|step| !(step.is_finite() && **step > 0.0)) {
+      return Err(format!("Retry segment duration {step} must be positive"));
+    }
+    let hints = self
+      .per_file_options
+      .iter()
+      .flatten()
+      .filter_map(|(_, options)| options.offset_hint_seconds)
+      .chain(self.offset_hint_seconds);
+    for hint in hints {
+      if !hint.is_finite() {
+        return Err(format!("Offset hint {hint} is not a number of seconds"));
+      }
+      if let Some(max) = self.max_offset_seconds.filter(|max| *max >= 0.0 && hint.abs() > *max) {
+        return Err(format!("Offset hint {hint}s is beyond the {max}s max offset"));
+      }
+    }
+    let downmixes = self
+      .per_file_options
+      .iter()
+      .flatten()
+      .filter_map(|(_, options)| options.downmix.as_deref())
+      .chain(self.downmix.as_deref());
+    for downmix in downmixes {
+      if !DOWNMIX_MODES.contains(&downmix) {
+        return Err(format!("Unknown downmix {downmix}; expected one of {}", DOWNMIX_MODES.join(", ")));
+      }
+    }
+    if let Some(min) = self.min_correlation_confidence {
+      if !(0.0..=1.0).contains(&min) {
+        return Err(format!("min_correlation_confidence {min} is outside 0–1"));
+      }
+    }
+    if let Some(points) = &self.anchor_points {
+      if points.is_empty() {
+        return Err("anchor_points needs at least one position".to_string());
+      }
+      if let Some(point) = points.iter().find(|point| !(0.0..=1.0).contains(*point)) {
+        return Err(format!("Anchor point {point} is outside 0–1"));
+      }
+    }
+    if let Some(template) = &self.filename_template {
+      render_filename_template(template, |_| Some(String::new()))?;
+    }
+    if let Some(dir) = &self.output_dir {
+      if Path::new(dir).exists() && !Path::new(dir).is_dir() {
+        return Err(format!("Output folder is a file: {dir}"));
+      }
+    }
+    self.check_output_names(pattern.as_ref())
+  }
+
+  /// Rejects output naming that would write over a source video: a template
+  /// that renders to just `{video_stem}` keeps the video's name and
+  /// extension, so it may only write to a folder other than the videos'.
+  /// Planned pairs are checked path by path.
+  fn check_output_names(&self, custom: Option<&Regex>) -> Result<(), String> {
+    let template = self.filename_template.as_deref().unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+    let marker = "\u{1}";
+    let rendered = render_filename_template(template, |token| {
+      Some(if token == "video_stem" { marker.to_string() } else { String::new() })
+    })?;
+    if rendered == marker {
+      let output_dir = self.output_dir.as_deref().map(|dir| resolved_path(Path::new(dir)));
+      let video_dirs: Vec<PathBuf> = self
+        .video_folders
+        .iter()
+        .flatten()
+        .chain(&self.video_folder)
+        .map(PathBuf::from)
+        .chain(
+          self
+            .video_files
+            .iter()
+            .flatten()
+            .chain(self.pairs.iter().flatten().map(|(video, _)| video))
+            .filter_map(|video| Path::new(video).parent().map(Path::to_path_buf)),
+        )
+        .map(|dir| resolved_path(&dir))
+        .collect();
+      if output_dir.as_ref().map_or(true, |dir| video_dirs.contains(dir)) {
+        return Err(format!(
+          "Filename template \"{template}\" keeps each video's name, so it needs an output folder other than the videos' own"
+        ));
+      }
+    }
+    let output = OutputTemplate {
+      output_dir: self.output_dir.as_deref(),
+      filename_template: self.filename_template.as_deref(),
+      custom,
+    };
+    for (video, audio) in self.pairs.iter().flatten() {
+      output.path(video, audio, Some(0.0), ApplyMode::Mux)?;
+    }
+    Ok(())
+  }
+
+  /// Event payload with `job_id` added for queued jobs, so the UI can route
+  /// it to the right row. Other payloads pass through unchanged.
+  fn tagged(&self, payload: impl Serialize) -> serde_json::Value {
+    tag_job(self.job_id, payload)
+  }
+
+  /// Bridge capabilities this request relies on, as named in its `hello`.
+  fn required_capabilities(&self) -> Vec<&'static str> {
+    let mut required = Vec::new();
+    if self.sample_rate.is_some() {
+      required.push("sample_rate");
+    }
+    if self.per_file_options.is_some() {
+      required.push("per_file_options");
+    }
+    let per_file_stream = self.per_file_options.iter().flatten().any(|(_, options)| options.video_audio_stream.is_some());
+    if self.video_audio_stream.is_some() || per_file_stream {
+      required.push("video_audio_stream");
+    }
+    let per_file_audio = self.per_file_options.iter().flatten().any(|(_, options)| options.audio_stream.is_some());
+    if self.audio_stream.is_some() || per_file_audio {
+      required.push("audio_stream");
+    }
+    if self.anchor_points.is_some() {
+      required.push("anchor_points");
+    }
+    if self.normalize == Some(true) {
+      required.push("normalize");
+    }
+    let per_file_hint = self.per_file_options.iter().flatten().any(|(_, options)| options.offset_hint_seconds.is_some());
+    if self.offset_hint_seconds.is_some() || per_file_hint {
+      required.push("offset_hint");
+    }
+    if self.algorithm.is_some() {
+      required.push("algorithm");
+    }
+    let per_file_downmix = self.per_file_options.iter().flatten().any(|(_, options)| options.downmix.is_some());
+    if self.downmix.is_some() || per_file_downmix {
+      required.push("downmix");
+    }
+    required
+  }
+
+  /// Segment duration used for `video`, after `per_file_options`.
+  fn segment_for(&self, video: &str) -> f64 {
+    self
+      .per_file_options
+      .as_ref()
+      .and_then(|options| options.get(video))
+      .and_then(|options| options.segment_duration)
+      .unwrap_or(self.segment_duration)
+  }
+
+  /// The planned pair a bridge result came from.
+  fn pair_of(&self, video_name: &str, audio_path: Option<&str>) -> Option<&(String, String)> {
+    self.pairs.iter().flatten().find(|(video, audio)| {
+      file_display_name(Path::new(video)) == video_name && audio_path.map_or(true, |path| path == audio)
+    })
+  }
+
+  /// Where applying a result with `delay_ms` would write, found through the
+  /// pair it came from. `None` for results without a delay.
+  fn output_path_for(&self, video_name: &str, audio_path: Option<&str>, delay_ms: Option<f64>) -> Option<String> {
+    delay_ms?;
+    let (video, audio) = self.pair_of(video_name, audio_path)?;
+    self
+      .output_file(video, audio, delay_ms)
+      .map(|path| path.to_string_lossy().to_string())
+  }
+
+  /// The muxed output `output_dir` and `filename_template` name for syncing
+  /// `audio` onto `video` with `delay_ms`.
+  fn output_file(&self, video: &str, audio: &str, delay_ms: Option<f64>) -> Option<PathBuf> {
+    let custom = compile_match_pattern(self.match_pattern.as_deref()).ok().flatten();
+    let template = OutputTemplate {
+      output_dir: self.output_dir.as_deref(),
+      filename_template: self.filename_template.as_deref(),
+      custom: custom.as_ref(),
+    };
+    template.path(video, audio, delay_ms, ApplyMode::Mux).ok()
+  }
+
+  fn video_stream_for(&self, video: &str) -> Option<u32> {
+    self
+      .per_file_options
+      .as_ref()
+      .and_then(|options| options.get(video))
+      .and_then(|options| options.video_audio_stream)
+      .or(self.video_audio_stream)
+  }
+
+  /// Stream of the audio file paired with `video`.
+  fn audio_stream_for(&self, video: &str) -> Option<u32> {
+    self
+      .per_file_options
+      .as_ref()
+      .and_then(|options| options.get(video))
+      .and_then(|options| options.audio_stream)
+      .or(self.audio_stream)
+  }
+
+  /// Notes bridge output for the `sync-stalled` timer.
+  fn beat(&self) {
+    if let Some(heartbeat) = &self.heartbeat {
+      heartbeat.beat();
+    }
+  }
+
+  /// Offset hint for `video`, after `per_file_options`.
+  fn offset_hint_for(&self, video: &str) -> Option<f64> {
+    self
+      .per_file_options
+      .as_ref()
+      .and_then(|options| options.get(video))
+      .and_then(|options| options.offset_hint_seconds)
+      .or(self.offset_hint_seconds)
+  }
+
+  /// Channel mix for `video`, after `per_file_options`.
+  fn downmix_for(&self, video: &str) -> Option<String> {
+    self
+      .per_file_options
+      .as_ref()
+      .and_then(|options| options.get(video))
+      .and_then(|options| options.downmix.clone())
+      .or_else(|| self.downmix.clone())
+  }
+
+  /// Language tags of the streams picked for the pair a result came from.
+  fn languages_of(&self, video_name: &str, audio_path: Option<&str>) -> (Option<String>, Option<String>) {
+    let options = self
+      .pair_of(video_name, audio_path)
+      .and_then(|(video, _)| self.per_file_options.as_ref()?.get(video));
+    match options {
+      Some(options) => (options.video_language.clone(), options.audio_language.clone()),
+      None => (None, None),
+    }
+  }
+
+  /// `audio_sources`, or `audio_folder` as the only source.
+  fn audio_sources(&self) -> Vec<AudioSource> {
+    match &self.audio_sources {
+      Some(sources) if !sources.is_empty() => sources.clone(),
+      _ => self
+        .audio_folder
+        .iter()
+        .map(|folder| AudioSource { folder: folder.clone(), label: None })
+        .collect(),
+    }
+  }
+
+  /// Label of the `audio_sources` entry holding `audio_path`; `None` without
+  /// `audio_sources`.
+  fn source_label(&self, audio_path: &str) -> Option<String> {
+    self.audio_sources.as_ref()?.iter().find_map(|source| {
+      Path::new(audio_path).starts_with(&source.folder).then(|| {
+        source
+          .label
+          .clone()
+          .unwrap_or_else(|| file_display_name(Path::new(&source.folder)))
+      })
+    })
+  }
+
+  /// Turns a result whose start or end delay (ms) lies beyond
+  /// `max_offset_seconds` in either direction into an error row.
+  fn limit_offset(&self, result: SyncResult) -> SyncResult {
+    let Some(max_seconds) = self.max_offset_seconds.filter(|max| *max >= 0.0) else {
+      return result;
+    };
+    let out_of_range = [result.startDelay, result.endDelay]
+      .into_iter()
+      .chain(result.delays.iter().map(|delay| delay.delay_ms))
+      .flatten()
+      .find(|delay| delay.abs() > max_seconds * 1000.0);
+    match out_of_range {
+      Some(delay) if result.error.is_none() => SyncResult {
+        startDelay: None,
+        endDelay: None,
+        delays: Vec::new(),
+        output_path: None,
+        drift_ms_per_min: None,
+        speed_factor: None,
+        speed_mismatch: None,
+        error: Some(format!(
+          "Delay of {:.1}s is beyond the {max_seconds}s max offset",
+          delay / 1000.0
+        )),
+        ..result
+      },
+      _ => result,
+    }
+  }
+
+  /// Index of the first pair outside `taken` a result belongs to: the exact
+  /// pair when both paths are known, otherwise the first one by video name.
+  fn pair_index(
+    &self,
+    video_name: &str,
+    video_path: Option<&str>,
+    audio_path: Option<&str>,
+    taken: &HashSet<usize>,
+  ) -> Option<usize> {
+    let pairs = self.pairs.as_deref()?;
+    (0..pairs.len()).filter(|index| !taken.contains(index)).find(|&index| {
+      let (video, audio) = &pairs[index];
+      match (video_path, audio_path) {
+        (Some(video_path), Some(audio_path)) => video == video_path && audio == audio_path,
+        _ => file_display_name(Path::new(video)) == video_name && audio_path.map_or(true, |path| path == audio),
+      }
+    })
+  }
+
+  /// Indices of the pairs `results` account for, one per result.
+  fn covered_pairs(&self, results: &[SyncResult]) -> HashSet<usize> {
+    let mut covered = HashSet::new();
+    for result in results {
+      let index = self.pair_index(&result.videoFile, result.video_path.as_deref(), result.audio_path.as_deref(), &covered);
+      covered.extend(index);
+    }
+    covered
+  }
+
+  /// The request for the pairs `results` doesn't cover yet, or `None` when
+  /// none are left.
+  fn remaining_after(&self, results: &[SyncResult]) -> Option<SyncRequest> {
+    let covered = self.covered_pairs(results);
+    let (pairs, confidence): (Vec<(String, String)>, Vec<Option<u8>>) = self
+      .pairs
+      .iter()
+      .flatten()
+      .enumerate()
+      .filter(|(index, _)| !covered.contains(index))
+      .map(|(index, pair)| (pair.clone(), self.pair_confidence.as_ref().and_then(|confidence| confidence.get(index)).copied()))
+      .unzip();
+    if pairs.is_empty() {
+      return None;
+    }
+    Some(SyncRequest {
+      pairs: Some(pairs),
+      pair_confidence: confidence.into_iter().collect(),
+      ..self.clone()
+    })
+  }
+
+  /// The pair `retry_failed` runs again for `result`: one that
+  /// `needs_retry` and has a `retry_escalation` step past its own segment.
+  /// Its row is emitted once, after the last attempt.
+  fn retry_pair(&self, result: &SyncResult) -> Option<&(String, String)> {
+    let steps = self.retry_escalation.as_ref()?;
+    let pair = self.pair_of(&result.videoFile, result.audio_path.as_deref())?;
+    let first = self.segment_for(&pair.0);
+    (needs_retry(result) && steps.iter().any(|&segment| segment > first)).then_some(pair)
+  }
+
+  /// One-pair request running `video` again with `segment` seconds.
+  fn retry_of(&self, video: &str, audio: &str, segment: f64) -> SyncRequest {
+    let index = self.pairs.iter().flatten().position(|(v, a)| v == video && a == audio);
+    let mut per_file_options = self.per_file_options.clone();
+    if let Some(options) = per_file_options.as_mut().and_then(|options| options.get_mut(video)) {
+      options.segment_duration = None;
+    }
+    SyncRequest {
+      pairs: Some(vec![(video.to_string(), audio.to_string())]),
+      pair_confidence: index
+        .and_then(|index| self.pair_confidence.as_ref()?.get(index))
+        .map(|&confidence| vec![confidence]),
+      segment_duration: segment,
+      per_file_options,
+      concurrency: Some(1),
+      fail_fast: false,
+      retry_escalation: None,
+      ..self.clone()
+    }
+  }
+
+  /// Marks an error-free bridge result whose `correlation_confidence` is
+  /// under `min_correlation_confidence` as `ResultStatus::LowConfidence`.
+  /// That outranks a drift `Warning`, as the drift comes from the same
+  /// doubtful delays; the drift itself stays on the result.
+  fn flag_confidence(&self, result: SyncResult) -> SyncResult {
+    if result.error.is_some() || !self.is_low_confidence(result.correlation_confidence) {
+      return result;
+    }
+    let status = match result.status {
+      None | Some(ResultStatus::Warning) => Some(ResultStatus::LowConfidence),
+      status => status,
+    };
+    SyncResult { status, ..result }
+  }
+
+  /// Drift past `max_drift_ms_per_min`, or `None`.
+  fn excess_drift(&self, result: &SyncResult) -> Option<f64> {
+    let limit = self.max_drift_ms_per_min.unwrap_or(DEFAULT_MAX_DRIFT_MS_PER_MIN);
+    result.drift_ms_per_min.filter(|drift| drift.abs() > limit)
+  }
+
+  /// True when a bridge-reported confidence is under
+  /// `min_correlation_confidence`.
+  fn is_low_confidence(&self, confidence: Option<f64>) -> bool {
+    matches!((confidence, self.min_correlation_confidence), (Some(confidence), Some(min)) if confidence < min)
+  }
+
+  /// Source label for a result row, preferring the bridge's full audio path
+  /// over a lookup of the pair by file names.
+  fn result_source(&self, video_name: &str, audio_name: &str, audio_path: Option<&str>) -> Option<String> {
+    let by_name = || {
+      self.pairs.iter().flatten().find_map(|(video, audio)| {
+        (file_display_name(Path::new(video)) == video_name && file_display_name(Path::new(audio)) == audio_name)
+          .then_some(audio.as_str())
+      })
+    };
+    self.source_label(audio_path.or_else(by_name)?)
+  }
+
+  /// Confidence recorded for the pair with these file names, if any.
+  fn confidence_of(&self, video_name: &str, audio_name: &str) -> Option<u8> {
+    let pairs = self.pairs.as_ref()?;
+    let confidence = self.pair_confidence.as_ref()?;
+    pairs
+      .iter()
+      .zip(confidence)
+      .find(|((video, audio), _)| {
+        file_display_name(Path::new(video)) == video_name && file_display_name(Path::new(audio)) == audio_name
+      })
+      .map(|(_, &confidence)| confidence)
+  }
+}
+
+/// Default for `SyncRequest.max_line_bytes`; a `done` message with hundreds
+/// of results is well under this.
+const DEFAULT_MAX_LINE_BYTES: usize = 8 * 1024 * 1024;
+
+fn string_or_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+  }
+
+  Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+    Some(OneOrMany::One(path)) => Some(vec![path]),
+    Some(OneOrMany::Many(paths)) => Some(paths),
+    None => None,
+  })
+}
+
+/// Reads a file name composed to NFC. The bridge echoes names as the OS
+/// listed them, which on macOS is decomposed, while requests and the UI hold
+/// them composed.
+fn nfc_name<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  String::deserialize(deserializer).map(|name| nfc(&name))
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct SyncResult {
+  #[serde(deserialize_with = "nfc_name")]
   videoFile: String,
+  #[serde(deserialize_with = "nfc_name")]
   audioFile: String,
   startDelay: Option<f64>,
   endDelay: Option<f64>,
   error: Option<String>,
   elapsedMs: Option<u64>,
+  /// Correlation confidence (0–1) reported by the bridge; `None` from bridges
+  /// that don't send one. Named apart from the UI's own `confidence` grade.
+  #[serde(default, rename = "correlationConfidence", alias = "confidence", skip_serializing_if = "Option::is_none")]
+  correlation_confidence: Option<f64>,
+  /// Segments the bridge correlated for this file, when it reports them.
+  #[serde(default, rename = "segmentsUsed", alias = "segments_used", skip_serializing_if = "Option::is_none")]
+  segments_used: Option<usize>,
+  /// Pairing confidence (0–100) for fuzzy-matched pairs.
+  #[serde(default, rename = "matchConfidence", skip_serializing_if = "Option::is_none")]
+  match_confidence: Option<u8>,
+  /// Analysis sample rate the bridge used, when it reports one.
+  #[serde(default, rename = "sampleRate", alias = "sample_rate", skip_serializing_if = "Option::is_none")]
+  sample_rate: Option<u32>,
+  /// ffprobe index of the video audio stream synced against, when one was picked.
+  #[serde(default, rename = "videoStream", alias = "video_stream", skip_serializing_if = "Option::is_none")]
+  video_stream: Option<u32>,
+  /// ffprobe index of the external audio stream synced, when one was picked.
+  #[serde(default, rename = "audioStream", alias = "audio_stream", skip_serializing_if = "Option::is_none")]
+  audio_stream: Option<u32>,
+  /// Language tag of the video audio stream picked through `video_language`.
+  #[serde(default, rename = "videoLanguage", skip_serializing_if = "Option::is_none")]
+  video_language: Option<String>,
+  /// Language tag of the external audio stream picked through `audio_language`.
+  #[serde(default, rename = "audioLanguage", skip_serializing_if = "Option::is_none")]
+  audio_language: Option<String>,
+  /// Delay at each of the request's `anchor_points`, in position order.
+  /// `startDelay`/`endDelay` repeat the first and last.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  delays: Vec<AnchorDelay>,
+  /// Where `apply_sync` should write this result (see `SyncRequest.output_dir`).
+  #[serde(default, rename = "outputPath", skip_serializing_if = "Option::is_none")]
+  output_path: Option<String>,
+  /// Change of delay per minute of video, from the start and end delays.
+  #[serde(default, rename = "driftMsPerMin", skip_serializing_if = "Option::is_none")]
+  drift_ms_per_min: Option<f64>,
+  /// Speed ratio that explains the drift, e.g. 1.04271 for a PAL speedup.
+  #[serde(default, rename = "speedFactor", skip_serializing_if = "Option::is_none")]
+  speed_factor: Option<f64>,
+  /// Frame rate conversion `speed_factor` matches, when it matches one.
+  #[serde(default, rename = "speedMismatch", skip_serializing_if = "Option::is_none")]
+  speed_mismatch: Option<SpeedMismatch>,
+  /// Level shift in dB `normalize` aimed for on the external audio: the
+  /// loudness target less `input_lufs`. loudnorm is dynamic, so the gain it
+  /// applied varies around this.
+  #[serde(default, rename = "gainDb", alias = "gain_db", skip_serializing_if = "Option::is_none")]
+  gain_db: Option<f64>,
+  /// Integrated loudness (LUFS) loudnorm measured on the external audio's
+  /// first window with `normalize`.
+  #[serde(default, rename = "inputLufs", alias = "input_lufs", skip_serializing_if = "Option::is_none")]
+  input_lufs: Option<f64>,
+  /// Runs this pair took with `retry_escalation`, the first included.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  attempts: Option<u32>,
+  /// Segment duration of the run this row comes from, with `retry_escalation`.
+  #[serde(default, rename = "segmentSecs", skip_serializing_if = "Option::is_none")]
+  segment_secs: Option<f64>,
+  /// Delay estimator the bridge measured with.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  algorithm: Option<String>,
+  /// The `offset_hint_seconds` the bridge centered its search on.
+  #[serde(default, rename = "offsetHintSeconds", skip_serializing_if = "Option::is_none")]
+  offset_hint_seconds: Option<f64>,
+  /// Channel mix the pair was analyzed with (see `DOWNMIX_MODES`); unset is
+  /// the bridge's plain mono downmix.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  downmix: Option<String>,
+  /// Set for rows that weren't synced by this run; `None` for a normal result.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  status: Option<ResultStatus>,
+  /// Label of the audio source the row's audio came from, with `audio_sources`.
+  #[serde(default, rename = "sourceLabel", skip_serializing_if = "Option::is_none")]
+  source_label: Option<String>,
+  /// Full video path as reported by the bridge; ties the row to its pair.
+  #[serde(default, rename = "videoPath", skip_serializing)]
+  video_path: Option<String>,
+  /// Full audio path as reported by the bridge; only used to find the source.
+  #[serde(default, rename = "audioPath", skip_serializing)]
+  audio_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum ResultStatus {
+  /// Its output already exists and is newer than both inputs (`skip_existing`).
+  Skipped,
+  /// A series file matching left without a partner.
+  Unmatched,
+  /// Synced, but drifting more than `max_drift_ms_per_min`.
+  Warning,
+  /// Would be synced; from a `dry_run`.
+  Planned,
+  /// Synced, but `correlation_confidence` is under the request's
+  /// `min_correlation_confidence`.
+  LowConfidence,
+  /// Abandoned after `file_timeout_secs` without progress.
+  TimedOut,
+}
+
+/// A suspected frame rate conversion between the video and its audio, such as
+/// a PAL speedup: the audio plays `audio_fps / video_fps` times too fast.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SpeedMismatch {
+  video_fps: f64,
+  audio_fps: f64,
+  /// e.g. `23.976 → 25 fps`.
+  label: String,
+  /// Set when at least three anchor delays lie on a straight line. Without
+  /// them only the start and end delays back the guess, and an unevenly
+  /// drifting capture could match a conversion by chance.
+  verified: bool,
+}
+
+impl SpeedMismatch {
+  /// `label`, noting when the drift wasn't shown to be linear.
+  fn describe(&self) -> String {
+    if self.verified {
+      self.label.clone()
+    } else {
+      format!("{} (unverified)", self.label)
+    }
+  }
+}
+
+/// Film and TV frame rates whose conversions `speed_mismatch_of` recognizes.
+const FILM_RATES: [(f64, &str); 3] = [(24000.0 / 1001.0, "23.976"), (24.0, "24"), (25.0, "25")];
+
+/// Largest relative difference between a speed factor and a frame rate ratio
+/// that still counts as that conversion. Tight enough to tell 24/23.976
+/// (0.1%) from no conversion at all.
+const SPEED_MATCH_TOLERANCE: f64 = 0.0003;
+
+/// Anchor delays may stray this share of the total drift (and at least
+/// `SPEED_FIT_FLOOR_MS`) from a straight line before the drift no longer
+/// looks like a constant speed difference.
+const SPEED_FIT_TOLERANCE: f64 = 0.01;
+const SPEED_FIT_FLOOR_MS: f64 = 40.0;
+
+/// The frame rate conversion `speed_factor` matches, if any. A known video
+/// frame rate narrows the candidates to conversions from it. With three or
+/// more anchors the drift must also be near-linear, as captures that drift
+/// unevenly aren't speed changes; with fewer, the start and end delays can't
+/// tell the two apart, so the match is flagged but not `verified`.
+fn speed_mismatch_of(speed_factor: f64, video_fps: Option<f64>, delays: &[AnchorDelay]) -> Option<SpeedMismatch> {
+  let measured: Vec<(f64, f64)> =
+    delays.iter().filter_map(|delay| delay.delay_ms.map(|ms| (delay.position, ms))).collect();
+  let verified = match measured.as_slice() {
+    [(first_pos, first), _, .., (last_pos, last)] => {
+      if last_pos <= first_pos {
+        return None;
+      }
+      let slope = (last - first) / (last_pos - first_pos);
+      let allowed = (SPEED_FIT_TOLERANCE * (last - first).abs()).max(SPEED_FIT_FLOOR_MS);
+      if measured.iter().any(|(pos, ms)| (ms - (first + slope * (pos - first_pos))).abs() > allowed) {
+        return None;
+      }
+      true
+    }
+    _ => false,
+  };
+  let video_rate = video_fps.and_then(|fps| FILM_RATES.iter().find(|(rate, _)| (rate - fps).abs() < 0.01));
+  FILM_RATES
+    .iter()
+    .filter(|video| video_rate.map_or(true, |rate| rate.1 == video.1))
+    .flat_map(|video| FILM_RATES.iter().filter(move |audio| audio.1 != video.1).map(move |audio| (video, audio)))
+    .find(|((video, _), (audio, _))| (speed_factor / (audio / video) - 1.0).abs() < SPEED_MATCH_TOLERANCE)
+    .map(|((video, video_label), (audio, audio_label))| SpeedMismatch {
+      video_fps: *video,
+      audio_fps: *audio,
+      label: format!("{video_label} → {audio_label} fps"),
+      verified,
+    })
+}
+
+/// `position` (0–1) as a percentage with at most two decimals, so 1/3 reads
+/// as `33.33` rather than the float's full expansion.
+fn percent_label(position: f64) -> String {
+  let label = format!("{:.2}", position * 100.0);
+  let (whole, fraction) = label.split_once('.').unwrap_or((&label, ""));
+  match fraction.trim_end_matches('0') {
+    "" => whole.to_string(),
+    fraction => format!("{whole}.{fraction}"),
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AnchorDelay {
+  /// Fraction of the runtime, 0–1.
+  position: f64,
+  /// The bridge and older history files spell it `delay_ms`.
+  #[serde(rename = "delayMs", alias = "delay_ms")]
+  delay_ms: Option<f64>,
+}
+
+impl SyncResult {
+  /// Row for a file matching left out; one of the names is empty.
+  fn unmatched(video_file: String, audio_file: String, source_label: Option<String>) -> Self {
+    SyncResult {
+      videoFile: video_file,
+      audioFile: audio_file,
+      startDelay: None,
+      endDelay: None,
+      error: Some("no match".to_string()),
+      elapsedMs: None,
+      correlation_confidence: None,
+      segments_used: None,
+      match_confidence: None,
+      sample_rate: None,
+      video_stream: None,
+      audio_stream: None,
+      delays: Vec::new(),
+      output_path: None,
+      drift_ms_per_min: None,
+      speed_factor: None,
+      speed_mismatch: None,
+      gain_db: None,
+      input_lufs: None,
+      attempts: None,
+      segment_secs: None,
+      algorithm: None,
+      offset_hint_seconds: None,
+      downmix: None,
+      video_language: None,
+      audio_language: None,
+      status: Some(ResultStatus::Unmatched),
+      source_label,
+      video_path: None,
+      audio_path: None,
+    }
+  }
+
+  /// Row for a pair rejected before the bridge ran.
+  fn failed(video_file: String, audio_file: String, error: String) -> Self {
+    SyncResult {
+      error: Some(error),
+      status: None,
+      ..SyncResult::unmatched(video_file, audio_file, None)
+    }
+  }
 }
 
+// Parsed and consumed one line at a time, so the large `Result` costs nothing.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
 enum BridgeMessage {
@@ -64,481 +1206,6291 @@ enum BridgeMessage {
   Log { message: String },
   #[serde(rename = "result")]
   Result {
+    #[serde(deserialize_with = "nfc_name")]
     videoFile: String,
+    #[serde(deserialize_with = "nfc_name")]
     audioFile: String,
     startDelay: Option<f64>,
     endDelay: Option<f64>,
     error: Option<String>,
     elapsed_ms: Option<u64>,
+    /// Sent by bridges that report full paths; older ones omit them.
+    #[serde(default, rename = "videoPath")]
+    video_path: Option<String>,
+    #[serde(default, rename = "audioPath")]
+    audio_path: Option<String>,
+    #[serde(default)]
+    confidence: Option<f64>,
+    #[serde(default)]
+    segments_used: Option<usize>,
+    #[serde(default)]
+    sample_rate: Option<u32>,
+    #[serde(default)]
+    video_stream: Option<u32>,
+    #[serde(default)]
+    audio_stream: Option<u32>,
+    #[serde(default)]
+    delays: Vec<AnchorDelay>,
+    #[serde(default)]
+    gain_db: Option<f64>,
+    #[serde(default)]
+    input_lufs: Option<f64>,
+    #[serde(default)]
+    algorithm: Option<String>,
   },
   #[serde(rename = "done")]
   Done { results: Vec<SyncResult> },
+  /// Answer to a `selftest` request; `stage` names the step that failed.
+  #[serde(rename = "self_test")]
+  SelfTest {
+    ok: bool,
+    #[serde(default)]
+    stage: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    delay_ms: Option<f64>,
+  },
+  /// First message of bridges that support the handshake.
+  #[serde(rename = "hello")]
+  Hello {
+    version: String,
+    #[serde(default)]
+    protocol: Option<u32>,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    /// Names `SyncRequest.algorithm` accepts; empty before the option.
+    #[serde(default)]
+    algorithms: Vec<String>,
+  },
+}
+
+/// Payload of the `sync-error` event, kept separate from `sync-log` chatter.
+#[derive(Debug, Serialize, Clone)]
+struct SyncError {
+  stage: String,
+  message: String,
+  file: Option<String>,
+}
+
+fn emit_sync_error(app: &AppHandle, job_id: Option<u64>, stage: &str, message: impl Into<String>, file: Option<String>) {
+  let error = SyncError {
+    stage: stage.to_string(),
+    message: message.into(),
+    file,
+  };
+  let _ = app.emit("sync-error", tag_job(job_id, error));
+}
+
+/// Emits a `sync-log` line as `{message}`, with `job_id` when set.
+fn emit_log(app: &AppHandle, job_id: Option<u64>, message: impl Into<String>) {
+  let _ = app.emit("sync-log", tag_job(job_id, serde_json::json!({ "message": message.into() })));
 }
 
 #[derive(Clone)]
 struct SyncState {
-  cancel: Arc<AtomicBool>,
+  /// Cancel tokens of the `start_sync` runs in flight, by job id.
+  running: Arc<Mutex<HashMap<u64, Arc<AtomicBool>>>>,
+  queue: Arc<JobQueue>,
 }
 
 impl SyncState {
   fn new() -> Self {
     Self {
-      cancel: Arc::new(AtomicBool::new(false)),
+      running: Arc::new(Mutex::new(HashMap::new())),
+      queue: Arc::new(JobQueue::default()),
     }
   }
+
+  /// A fresh id shared by `start_sync` runs and queued jobs.
+  fn next_job_id(&self) -> u64 {
+    self.queue.next_id.fetch_add(1, Ordering::SeqCst) + 1
+  }
+
+  /// The caller's `requested` id, or a fresh one. A caller's id may not name
+  /// a queued job, and is reserved so `next_job_id` never hands it out later.
+  fn claim_job_id(&self, requested: Option<u64>) -> Result<u64, AppError> {
+    let Some(id) = requested else {
+      return Ok(self.next_job_id());
+    };
+    let jobs = self.queue.jobs.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    if jobs.iter().any(|job| job.id == id) {
+      return Err(AppError::InvalidRequest(format!("Job id {id} is already in use")));
+    }
+    self.queue.next_id.fetch_max(id, Ordering::SeqCst);
+    Ok(id)
+  }
 }
 
-async fn pick_folder_async(window: Window) -> Option<PathBuf> {
-  let (tx, rx) = std::sync::mpsc::channel::<Option<PathBuf>>();
-  window.dialog().file().pick_folder(move |path| {
-    let resolved = path.and_then(|p| p.into_path().ok());
-    let _ = tx.send(resolved);
-  });
-  tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
-    .await
-    .ok()
-    .flatten()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum JobStatus {
+  Queued,
+  Running,
+  Done,
+  Failed,
+  Canceled,
 }
 
-async fn pick_file_async(window: Window) -> Option<PathBuf> {
-  let (tx, rx) = std::sync::mpsc::channel::<Option<PathBuf>>();
-  window.dialog().file().pick_file(move |path| {
-    let resolved = path.and_then(|p| p.into_path().ok());
-    let _ = tx.send(resolved);
-  });
-  tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
-    .await
-    .ok()
-    .flatten()
+struct QueuedJob {
+  id: u64,
+  request: SyncRequest,
+  status: JobStatus,
+  cancel: Arc<AtomicBool>,
+  error: Option<String>,
+  result_count: usize,
 }
 
-async fn save_file_async(window: Window, default_name: &str) -> Option<PathBuf> {
-  let (tx, rx) = std::sync::mpsc::channel::<Option<PathBuf>>();
-  window
+#[derive(Debug, Clone, Serialize)]
+struct JobInfo {
+  id: u64,
+  status: JobStatus,
+  request: SyncRequest,
+  error: Option<String>,
+  result_count: usize,
+}
+
+impl QueuedJob {
+  fn info(&self) -> JobInfo {
+    JobInfo {
+      id: self.id,
+      status: self.status,
+      request: self.request.clone(),
+      error: self.error.clone(),
+      result_count: self.result_count,
+    }
+  }
+}
+
+/// Jobs from `enqueue_sync`, run one at a time by a worker thread started
+/// with the first job. Finished jobs stay listed for the session.
+#[derive(Default)]
+struct JobQueue {
+  jobs: Mutex<Vec<QueuedJob>>,
+  wake: std::sync::Condvar,
+  next_id: AtomicU64,
+  worker_started: AtomicBool,
+}
+
+impl JobQueue {
+  /// Updates job `id` and emits its new state as `sync-job`.
+  fn update(&self, app: &AppHandle, id: u64, change: impl FnOnce(&mut QueuedJob)) {
+    let Ok(mut jobs) = self.jobs.lock() else {
+      return;
+    };
+    if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+      change(job);
+      let _ = app.emit("sync-job", job.info());
+    }
+  }
+
+  /// Blocks until a job is queued, marks it running and hands it out.
+  fn next(&self, app: &AppHandle) -> Option<(SyncRequest, Arc<AtomicBool>)> {
+    let mut jobs = self.jobs.lock().ok()?;
+    loop {
+      if let Some(job) = jobs.iter_mut().find(|job| job.status == JobStatus::Queued) {
+        job.status = JobStatus::Running;
+        let _ = app.emit("sync-job", job.info());
+        return Some((job.request.clone(), job.cancel.clone()));
+      }
+      jobs = self.wake.wait(jobs).ok()?;
+    }
+  }
+}
+
+fn run_job_queue(app: AppHandle, queue: Arc<JobQueue>) {
+  while let Some((request, cancel)) = queue.next(&app) {
+    let id = request.job_id.unwrap_or_default();
+    let job = SyncRequest { job_id: None, ..request.clone() };
+    let outcome = run_bridge(app.clone(), request, cancel.clone());
+    let canceled = cancel.load(Ordering::SeqCst);
+    if outcome.is_ok() && !job.dry_run && !canceled {
+      if let Err(err) = remember_job(&app, job) {
+        log::warn!("Failed to remember sync job: {err}");
+      }
+    }
+    queue.update(&app, id, |job| match outcome {
+      Ok(results) => {
+        job.result_count = results.len();
+        job.status = if canceled { JobStatus::Canceled } else { JobStatus::Done };
+      }
+      Err(err) => {
+        job.error = Some(err.to_string());
+        job.status = JobStatus::Failed;
+      }
+    });
+  }
+}
+
+/// Probe results keyed by path, each tagged with the file's mtime when probed.
+#[derive(Default)]
+struct ProbeCache {
+  entries: Mutex<HashMap<PathBuf, (SystemTime, MediaProbe)>>,
+}
+
+async fn pick_folder_async(window: Window, role: &str) -> Option<PathBuf> {
+  let app = window.app_handle().clone();
+  let (tx, rx) = std::sync::mpsc::channel::<Option<PathBuf>>();
+  dialog_for(&window, &app, role).pick_folder(move |path| {
+    let resolved = path.and_then(|p| p.into_path().ok());
+    let _ = tx.send(resolved);
+  });
+  let picked = tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
+    .await
+    .ok()
+    .flatten();
+  if let Some(folder) = &picked {
+    remember_directory(&app, role, folder);
+  }
+  picked
+}
+
+async fn pick_folders_async(window: Window, role: &str) -> Option<Vec<PathBuf>> {
+  let app = window.app_handle().clone();
+  let (tx, rx) = std::sync::mpsc::channel::<Option<Vec<PathBuf>>>();
+  dialog_for(&window, &app, role).pick_folders(move |paths| {
+    let resolved = paths.map(|paths| {
+      paths
+        .into_iter()
+        .filter_map(|p| p.into_path().ok())
+        .collect::<Vec<_>>()
+    });
+    let _ = tx.send(resolved);
+  });
+  let picked = tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
+    .await
+    .ok()
+    .flatten();
+  if let Some(folder) = picked.as_ref().and_then(|folders| folders.first()) {
+    remember_directory(&app, role, folder);
+  }
+  picked
+}
+
+async fn pick_files_async(window: Window, role: &str, filters: &[DialogFilter]) -> Option<Vec<PathBuf>> {
+  let app = window.app_handle().clone();
+  let (tx, rx) = std::sync::mpsc::channel::<Option<Vec<PathBuf>>>();
+  let builder = filters
+    .iter()
+    .fold(dialog_for(&window, &app, role), |builder, (name, exts)| builder.add_filter(*name, exts));
+  builder.pick_files(move |paths| {
+    let resolved = paths.map(|paths| {
+      paths
+        .into_iter()
+        .filter_map(|p| p.into_path().ok())
+        .collect::<Vec<_>>()
+    });
+    let _ = tx.send(resolved);
+  });
+  let picked = tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
+    .await
+    .ok()
+    .flatten();
+  if let Some(parent) = picked
+    .as_ref()
+    .and_then(|files| files.first())
+    .and_then(|file| file.parent())
+  {
+    remember_directory(&app, role, parent);
+  }
+  picked
+}
+
+async fn save_file_async(window: Window, default_name: &str, filter: DialogFilter) -> Option<PathBuf> {
+  let app = window.app_handle().clone();
+  // Exports land next to the videos of the last sync unless the user has
+  // since saved somewhere else.
+  let settings = load_settings_file(&app);
+  let directory = settings
+    .last_sync_video_folder
+    .or_else(|| settings.recent_directories.get("export").cloned())
+    .map(PathBuf::from)
+    .filter(|dir| dir.is_dir());
+  let mut builder = window
     .dialog()
     .file()
     .set_file_name(default_name)
-    .save_file(move |path| {
-      let resolved = path.and_then(|p| p.into_path().ok());
-      let _ = tx.send(resolved);
-    });
-  tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
+    .add_filter(filter.0, filter.1);
+  if let Some(directory) = directory {
+    builder = builder.set_directory(directory);
+  }
+
+  let (tx, rx) = std::sync::mpsc::channel::<Option<PathBuf>>();
+  builder.save_file(move |path| {
+    let resolved = path.and_then(|p| p.into_path().ok());
+    let _ = tx.send(resolved);
+  });
+  let picked = tauri::async_runtime::spawn_blocking(move || rx.recv().ok().flatten())
     .await
     .ok()
-    .flatten()
+    .flatten();
+  if let Some(parent) = picked.as_ref().and_then(|file| file.parent()) {
+    remember_directory(&app, "export", parent);
+  }
+  picked
+}
+
+/// File dialog starting in the last directory used for `role`.
+fn dialog_for(
+  window: &Window,
+  app: &AppHandle,
+  role: &str,
+) -> tauri_plugin_dialog::FileDialogBuilder<tauri::Wry> {
+  let builder = window.dialog().file();
+  match recent_directory(app, role) {
+    Some(directory) => builder.set_directory(directory),
+    None => builder,
+  }
+}
+
+/// Preferences persisted as JSON in the app config directory.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct Settings {
+  /// Last directory picked per dialog role (`video`, `audio`, `export`).
+  recent_directories: HashMap<String, String>,
+  last_sync_video_folder: Option<String>,
+  /// User-chosen ffmpeg binary, or the folder holding ffmpeg and ffprobe.
+  ffmpeg_path: Option<String>,
+  /// Default for `ListOptions.min_size_bytes`.
+  min_size_bytes: u64,
+  #[serde(flatten)]
+  session: SessionSettings,
+  /// Completed sync requests, newest first, capped at `MAX_RECENT_JOBS`.
+  recent_jobs: Vec<RecentJob>,
+}
+
+const MAX_RECENT_JOBS: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RecentJob {
+  /// The request as the UI sent it, before pairs were resolved, so a rerun
+  /// picks up files added since.
+  request: SyncRequest,
+  /// Unix milliseconds when the sync finished.
+  finished_at: u64,
+}
+
+/// The part of the settings the UI restores on launch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct SessionSettings {
+  last_video_folder: Option<String>,
+  last_audio_folder: Option<String>,
+  segment_duration: Option<f64>,
+  match_pattern: Option<String>,
+  /// Default for `SyncRequest.low_priority`, also applied to ffmpeg and
+  /// ffprobe runs outside a sync.
+  low_priority: bool,
+  /// Default for `SyncRequest.normalize`.
+  normalize: bool,
+}
+
+/// The stored `SessionSettings.low_priority`, kept here so every spawn can
+/// check it without reading the settings file.
+static LOW_PRIORITY: AtomicBool = AtomicBool::new(false);
+
+/// Serializes read-modify-write cycles on the settings file.
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join("settings.json"))
+}
+
+/// Loads settings, falling back to defaults when the file is missing or corrupt.
+fn load_settings_file(app: &AppHandle) -> Settings {
+  settings_path(app)
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|raw| serde_json::from_str(&raw).ok())
+    .unwrap_or_default()
+}
+
+/// Writes settings atomically via a temp file and rename.
+fn save_settings_file(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+  let path = settings_path(app).ok_or_else(|| "App config directory unavailable".to_string())?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+  }
+  let json = serde_json::to_string_pretty(settings).map_err(|err| err.to_string())?;
+  let tmp = path.with_extension("json.tmp");
+  fs::write(&tmp, json.as_bytes()).map_err(|err| err.to_string())?;
+  fs::rename(&tmp, &path).map_err(|err| err.to_string())
+}
+
+fn update_settings(app: &AppHandle, update: impl FnOnce(&mut Settings)) -> Result<(), String> {
+  let _guard = SETTINGS_LOCK.lock().map_err(|err| err.to_string())?;
+  let mut settings = load_settings_file(app);
+  update(&mut settings);
+  save_settings_file(app, &settings)
+}
+
+fn recent_directory(app: &AppHandle, role: &str) -> Option<PathBuf> {
+  let settings = load_settings_file(app);
+  // Fall back to the folders the UI last saved for its session.
+  let session_folder = match role {
+    "video" => settings.session.last_video_folder.as_deref(),
+    "audio" => settings.session.last_audio_folder.as_deref(),
+    _ => None,
+  };
+  settings
+    .recent_directories
+    .get(role)
+    .map(String::as_str)
+    .into_iter()
+    .chain(session_folder)
+    .map(PathBuf::from)
+    .find(|dir| dir.is_dir())
+}
+
+fn remember_directory(app: &AppHandle, role: &str, directory: &Path) {
+  let directory = directory.to_string_lossy().to_string();
+  let result = update_settings(app, |settings| {
+    settings.recent_directories.insert(role.to_string(), directory);
+  });
+  if let Err(err) = result {
+    log::warn!("Failed to remember {role} directory: {err}");
+  }
 }
 
 #[tauri::command]
-async fn pick_video_files(window: Window, mode: String) -> Result<PickResponse, String> {
-  if mode != "movie" && mode != "series" {
-    return Ok(PickResponse {
-      folder: None,
-      files: Vec::new(),
-    });
+fn load_settings(app: AppHandle) -> SessionSettings {
+  load_settings_file(&app).session
+}
+
+#[tauri::command]
+fn save_settings(app: AppHandle, settings: SessionSettings) -> Result<(), AppError> {
+  LOW_PRIORITY.store(settings.low_priority, Ordering::SeqCst);
+  update_settings(&app, |stored| stored.session = settings).map_err(AppError::Io)
+}
+
+/// Makes `command` start at below-normal priority.
+fn lower_priority(command: &mut Command) {
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+    command.creation_flags(BELOW_NORMAL_PRIORITY_CLASS);
+  }
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::CommandExt;
+    // SAFETY: the hook only makes an async-signal-safe libc call on the
+    // child itself; threads it starts later inherit the niceness.
+    unsafe {
+      command.pre_exec(|| {
+        libc::setpriority(libc::PRIO_PROCESS, 0, 10);
+        Ok(())
+      });
+    }
   }
+}
 
-  let folder = pick_folder_async(window).await;
-  let folder = match folder {
-    Some(path) => path,
-    None => {
-      return Ok(PickResponse {
-        folder: None,
-        files: Vec::new(),
-      })
+/// How long to wait for the bridge's processes to exit after killing them.
+const TREE_EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The bridge and the processes it starts, such as ffmpeg decoders, so a
+/// cancel or timeout stops them all instead of leaving orphans that hold file
+/// locks. On Unix the bridge leads its own process group; on Windows it joins
+/// a job object, with `taskkill /T` as the fallback when that fails.
+struct ProcessTree {
+  child: Arc<Mutex<Child>>,
+  pid: u32,
+  /// Run the tree belongs to, for tagging its log lines.
+  job_id: Option<u64>,
+  #[cfg(windows)]
+  job: Option<job_object::Job>,
+}
+
+impl ProcessTree {
+  /// Sets up `command` so its descendants can be found later.
+  fn isolate(command: &mut Command) {
+    #[cfg(unix)]
+    {
+      use std::os::unix::process::CommandExt;
+      command.process_group(0);
     }
-  };
+    #[cfg(not(unix))]
+    let _ = command;
+  }
 
-  let files = if mode == "movie" {
-    list_movie_videos(&folder)
-  } else {
-    list_folder_files(&folder)
+  /// Call right after spawning, before the child is sent any work.
+  fn attach(child: Child, job_id: Option<u64>) -> Self {
+    ProcessTree {
+      pid: child.id(),
+      job_id,
+      #[cfg(windows)]
+      job: job_object::Job::for_child(&child),
+      child: Arc::new(Mutex::new(child)),
+    }
+  }
+
+  fn kill(&self) {
+    #[cfg(unix)]
+    // SAFETY: plain libc call; a negative pid signals the bridge's group.
+    unsafe {
+      libc::kill(-(self.pid as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    {
+      use std::os::windows::process::CommandExt;
+      const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+      if !self.job.as_ref().is_some_and(job_object::Job::terminate) {
+        let _ = Command::new("taskkill")
+          .args(["/T", "/F", "/PID", &self.pid.to_string()])
+          .creation_flags(CREATE_NO_WINDOW)
+          .stdout(Stdio::null())
+          .stderr(Stdio::null())
+          .status();
+      }
+    }
+    if let Ok(mut child) = self.child.lock() {
+      let _ = child.kill();
+    }
+  }
+
+  /// Whether any process of the tree other than an unreaped bridge is left.
+  fn alive(&self) -> bool {
+    #[cfg(unix)]
+    // SAFETY: signal 0 only checks that the group exists.
+    let alive = unsafe { libc::kill(-(self.pid as libc::pid_t), 0) == 0 };
+    #[cfg(windows)]
+    let alive = self.job.as_ref().and_then(job_object::Job::active_processes).is_some_and(|count| count > 0);
+    #[cfg(not(any(unix, windows)))]
+    let alive = false;
+    alive
+  }
+
+  /// Kills the tree and waits up to `TREE_EXIT_TIMEOUT` for it to go,
+  /// logging when something outlives that.
+  fn stop(&self, app: &AppHandle) {
+    self.kill();
+    let deadline = Instant::now() + TREE_EXIT_TIMEOUT;
+    while Instant::now() < deadline {
+      // Reaps the bridge, which would otherwise still count as alive.
+      if child_exited(&self.child) && !self.alive() {
+        return;
+      }
+      std::thread::sleep(Duration::from_millis(50));
+    }
+    let message = format!("Processes started by the bridge (pid {}) are still running after the kill.", self.pid);
+    log::warn!("{message}");
+    emit_log(app, self.job_id, message);
+  }
+}
+
+#[cfg(windows)]
+mod job_object {
+  use std::os::windows::io::AsRawHandle;
+  use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+  use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
+    JobObjectExtendedLimitInformation, QueryInformationJobObject, SetInformationJobObject, TerminateJobObject,
+    JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
   };
 
-  Ok(PickResponse {
-    folder: Some(folder.to_string_lossy().to_string()),
-    files,
+  /// A job that kills its processes when terminated or closed.
+  pub struct Job(HANDLE);
+
+  // SAFETY: job handles may be used from any thread.
+  unsafe impl Send for Job {}
+  unsafe impl Sync for Job {}
+
+  impl Job {
+    pub fn for_child(child: &std::process::Child) -> Option<Job> {
+      // SAFETY: the handle is checked before use and closed by `Drop`.
+      unsafe {
+        let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if handle.is_null() {
+          return None;
+        }
+        let job = Job(handle);
+        let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        let limited = SetInformationJobObject(
+          handle,
+          JobObjectExtendedLimitInformation,
+          &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const _,
+          std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        ) != 0;
+        (limited && AssignProcessToJobObject(handle, child.as_raw_handle()) != 0).then_some(job)
+      }
+    }
+
+    pub fn terminate(&self) -> bool {
+      // SAFETY: `self.0` is a live job handle.
+      unsafe { TerminateJobObject(self.0, 1) != 0 }
+    }
+
+    pub fn active_processes(&self) -> Option<u32> {
+      let mut info = JOBOBJECT_BASIC_ACCOUNTING_INFORMATION::default();
+      // SAFETY: `self.0` is a live job handle and `info` is the struct its
+      // class asks for.
+      let ok = unsafe {
+        QueryInformationJobObject(
+          self.0,
+          JobObjectBasicAccountingInformation,
+          &mut info as *mut JOBOBJECT_BASIC_ACCOUNTING_INFORMATION as *mut _,
+          std::mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+          std::ptr::null_mut(),
+        )
+      };
+      (ok != 0).then_some(info.ActiveProcesses)
+    }
+  }
+
+  impl Drop for Job {
+    fn drop(&mut self) {
+      // SAFETY: closing our own handle once; remaining members are killed.
+      unsafe {
+        CloseHandle(self.0);
+      }
+    }
+  }
+}
+
+/// Whether to run at below-normal priority: `low_priority` as a request
+/// gives it, else the settings' default.
+fn wants_low_priority(low_priority: Option<bool>) -> bool {
+  low_priority.unwrap_or_else(|| LOW_PRIORITY.load(Ordering::SeqCst))
+}
+
+/// `Command` for an ffmpeg tool, at low priority when `low_priority` (see
+/// `wants_low_priority`) asks for it.
+fn tool_command(program: &Path, low_priority: Option<bool>) -> Command {
+  let mut command = Command::new(program);
+  if wants_low_priority(low_priority) {
+    lower_priority(&mut command);
+  }
+  command
+}
+
+#[tauri::command]
+fn clear_recent_directories(app: AppHandle) -> Result<(), AppError> {
+  update_settings(&app, |settings| {
+    settings.recent_directories.clear();
+    settings.last_sync_video_folder = None;
   })
+  .map_err(AppError::Io)
 }
 
+/// Picks a video folder and lists it. With `pick_files`, movie mode picks
+/// individual videos instead, reporting picks that aren't videos in `errors`.
 #[tauri::command]
-async fn pick_audio_files(window: Window, mode: String) -> Result<PickResponse, String> {
-  if mode == "movie" {
-    let file = pick_file_async(window).await;
-    let file = match file {
-      Some(path) => path,
-      None => {
-        return Ok(PickResponse {
-          folder: None,
-          files: Vec::new(),
-        })
-      }
+async fn pick_video_files(
+  window: Window,
+  mode: String,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+  pick_files: Option<bool>,
+) -> Result<PickResponse, AppError> {
+  let app = window.app_handle().clone();
+  let options = options.unwrap_or_default().with_saved_min_size(&app);
+  options.name_globs().map_err(AppError::InvalidRequest)?;
+  if mode != "movie" && mode != "series" {
+    return Ok(PickResponse::default());
+  }
+  if mode == "movie" && pick_files.unwrap_or(false) {
+    let picked = match pick_files_async(window, "video", &VIDEO_FILTERS).await {
+      Some(picked) if !picked.is_empty() => picked,
+      _ => return Ok(PickResponse::default()),
     };
-    let name = file
-      .file_name()
-      .map(|s| s.to_string_lossy().to_string())
-      .unwrap_or_default();
-    let size = fs::metadata(&file).map(|meta| meta.len()).ok();
-    return Ok(PickResponse {
-      folder: file.parent().map(|p| p.to_string_lossy().to_string()),
-      files: vec![FileItem {
-        name,
+    let (videos, rejected): (Vec<PathBuf>, Vec<PathBuf>) = picked.into_iter().partition(|file| is_movie_video(file));
+    let errors = rejected
+      .iter()
+      .map(|file| PathError {
         path: file.to_string_lossy().to_string(),
-        file_type: "audio".to_string(),
-        size,
-      }],
+        message: "Not a video file".to_string(),
+      })
+      .collect();
+    let files = picked_items(&videos, "video", &options).await.map_err(AppError::Io)?;
+    let mut files = if options.probe_durations {
+      with_durations(app, files).await.map_err(AppError::ProbeFailed)?
+    } else {
+      files
+    };
+    if let Some(sort) = sort {
+      sort_files(&mut files, sort);
+    }
+    let parents: Vec<PathBuf> = videos
+      .iter()
+      .filter_map(|file| file.parent().map(Path::to_path_buf))
+      .collect();
+    let folders = distinct_folders(&parents);
+    return Ok(PickResponse {
+      folder: common_ancestor(&parents).map(|p| p.to_string_lossy().to_string()),
+      folders,
+      files,
+      errors,
+      warnings: Vec::new(),
+      skipped_small: 0,
+      listing_id: None,
     });
   }
 
-  let folder = pick_folder_async(window).await;
+  let folder = pick_folder_async(window, "video").await;
   let folder = match folder {
     Some(path) => path,
     None => {
-      return Ok(PickResponse {
-        folder: None,
-        files: Vec::new(),
-      })
+      return Ok(PickResponse::default())
     }
   };
+  if options.stream {
+    let kind = if mode == "movie" { ListingKind::MovieVideos } else { ListingKind::FolderFiles };
+    return Ok(streamed_response(&app, folder, kind, options));
+  }
 
-  let files = list_folder_files(&folder)
-    .into_iter()
-    .map(|mut item| {
-      item.file_type = "audio".to_string();
-      item
-    })
-    .collect();
+  let kind = if mode == "movie" { ListingKind::MovieVideos } else { ListingKind::FolderFiles };
+  let Listing { files, skipped_small } = list_guarded(&folder, &options, kind).await.map_err(AppError::Io)?;
+  let mut files = if options.probe_durations {
+    with_durations(app, files).await.map_err(AppError::ProbeFailed)?
+  } else {
+    files
+  };
+  if let Some(sort) = sort {
+    sort_files(&mut files, sort);
+  }
 
+  let folder = folder.to_string_lossy().to_string();
   Ok(PickResponse {
-    folder: Some(folder.to_string_lossy().to_string()),
+    folder: Some(folder.clone()),
+    folders: vec![folder],
     files,
+    errors: Vec::new(),
+    warnings: Vec::new(),
+    skipped_small,
+    listing_id: None,
   })
 }
 
-#[tauri::command]
-async fn start_sync(
-  app: AppHandle,
-  state: State<'_, SyncState>,
-  request: SyncRequest,
-) -> Result<Vec<SyncResult>, String> {
-  state.cancel.store(false, Ordering::SeqCst);
-  let handle = app.clone();
-  let cancel = state.cancel.clone();
-  tauri::async_runtime::spawn_blocking(move || run_bridge(handle, request, cancel))
-    .await
-    .map_err(|err| err.to_string())?
+/// Short labels for `folders`: each folder's name, extended with parent
+/// folders (`Show A/Season 1`) while it matches another folder's label.
+fn folder_labels(folders: &[PathBuf]) -> Vec<String> {
+  let components: Vec<Vec<String>> = folders
+    .iter()
+    .map(|folder| {
+      folder
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect()
+    })
+    .collect();
+  let label = |parts: &[String], depth: usize| parts[parts.len().saturating_sub(depth)..].join("/");
+  let mut depths = vec![1; folders.len()];
+  loop {
+    let labels: Vec<String> = components.iter().zip(&depths).map(|(parts, &depth)| label(parts, depth)).collect();
+    let mut grew = false;
+    for (index, parts) in components.iter().enumerate() {
+      let clashes = labels.iter().enumerate().any(|(other, name)| other != index && *name == labels[index]);
+      if clashes && depths[index] < parts.len() {
+        depths[index] += 1;
+        grew = true;
+      }
+    }
+    if !grew {
+      return folders
+        .iter()
+        .zip(labels)
+        .map(|(folder, label)| if label.is_empty() { folder.to_string_lossy().to_string() } else { label })
+        .collect();
+    }
+  }
 }
 
 #[tauri::command]
-async fn export_csv(window: Window, results: Vec<SyncResult>) -> Result<String, String> {
-  let path = save_file_async(window, "sync-results.csv").await;
-  let Some(path) = path else {
-    return Err("Export canceled".to_string());
-  };
-
-  let mut csv = String::from("Video,Audio,Start Delay (ms),End Delay (ms),Elapsed (ms),Error\n");
-  for result in results {
-    let start = result.startDelay.map(|v| v.to_string()).unwrap_or_default();
-    let end = result.endDelay.map(|v| v.to_string()).unwrap_or_default();
-    let elapsed = result.elapsedMs.map(|v| v.to_string()).unwrap_or_default();
-    let err = result.error.unwrap_or_default();
-    csv.push_str(&format!(
-      "\"{}\",\"{}\",{},{},{},\"{}\"\n",
-      result.videoFile, result.audioFile, start, end, elapsed, err
-    ));
+async fn pick_video_folders(
+  window: Window,
+  mode: String,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  let app = window.app_handle().clone();
+  let options = options.unwrap_or_default().with_saved_min_size(&app);
+  options.name_globs().map_err(AppError::InvalidRequest)?;
+  if mode != "movie" && mode != "series" {
+    return Ok(PickResponse::default());
   }
 
-  fs::write(&path, csv.as_bytes()).map_err(|err| err.to_string())?;
-  Ok(path.to_string_lossy().to_string())
-}
+  let folders = match pick_folders_async(window, "video").await {
+    Some(folders) if !folders.is_empty() => folders,
+    _ => return Ok(PickResponse::default()),
+  };
 
-fn list_movie_videos(folder: &Path) -> Vec<FileItem> {
-  let mut items = Vec::new();
-  let exts = ["mp4", "mkv", "webm", "avi", "mov"];
-  if let Ok(entries) = fs::read_dir(folder) {
-    for entry in entries.flatten() {
-      let path = entry.path();
-      if !path.is_file() {
-        continue;
-      }
-      let size = fs::metadata(&path).map(|meta| meta.len()).ok();
-      let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-      if !exts.contains(&ext.as_str()) {
-        continue;
+  let mut files = Vec::new();
+  let mut skipped_small = 0;
+  let labels = folder_labels(&folders);
+  for (folder, label) in folders.iter().zip(&labels) {
+    let kind = if mode == "movie" { ListingKind::MovieVideos } else { ListingKind::FolderFiles };
+    let listing = list_guarded(folder, &options, kind).await.map_err(AppError::Io)?;
+    skipped_small += listing.skipped_small;
+    let mut listed = listing.files;
+    // Prefix with the folder label so identical filenames from different
+    // folders stay distinguishable in the UI.
+    if folders.len() > 1 {
+      for item in &mut listed {
+        item.name = format!("{label}/{}", item.name);
       }
-      let name = path.file_name().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
-      items.push(FileItem {
-        name,
-        path: path.to_string_lossy().to_string(),
-        file_type: "video".to_string(),
-        size,
-      });
     }
+    files.extend(listed);
   }
-  items
+  let mut files = if options.probe_durations {
+    with_durations(app, files).await.map_err(AppError::ProbeFailed)?
+  } else {
+    files
+  };
+  if let Some(sort) = sort {
+    sort_files(&mut files, sort);
+  }
+
+  Ok(PickResponse {
+    folder: common_ancestor(&folders).map(|p| p.to_string_lossy().to_string()),
+    folders: folders.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+    files,
+    errors: Vec::new(),
+    warnings: Vec::new(),
+    skipped_small,
+    listing_id: None,
+  })
 }
 
-fn list_folder_files(folder: &Path) -> Vec<FileItem> {
-  let mut items = Vec::new();
-  if let Ok(entries) = fs::read_dir(folder) {
-    for entry in entries.flatten() {
-      let path = entry.path();
-      if !path.is_file() {
-        continue;
-      }
-      let size = fs::metadata(&path).map(|meta| meta.len()).ok();
-      let name = path.file_name().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
-      items.push(FileItem {
-        name,
-        path: path.to_string_lossy().to_string(),
-        file_type: "video".to_string(),
-        size,
-      });
+/// `folders` as strings, each once, in first-seen order.
+fn distinct_folders(folders: &[PathBuf]) -> Vec<String> {
+  let mut seen = HashSet::new();
+  folders
+    .iter()
+    .filter(|folder| seen.insert(*folder))
+    .map(|folder| folder.to_string_lossy().to_string())
+    .collect()
+}
+
+/// Longest shared leading path of `paths`, if any.
+fn common_ancestor(paths: &[PathBuf]) -> Option<PathBuf> {
+  let (first, rest) = paths.split_first()?;
+  let mut ancestor = first.clone();
+  while !rest.iter().all(|path| path.starts_with(&ancestor)) {
+    if !ancestor.pop() {
+      return None;
     }
   }
-  items
+  if ancestor.as_os_str().is_empty() {
+    None
+  } else {
+    Some(ancestor)
+  }
 }
 
-fn find_sidecar_path(app: &AppHandle) -> Option<PathBuf> {
-  let sidecar_name = if cfg!(windows) {
-    "audiosync-cli-x86_64-pc-windows-msvc.exe"
-  } else {
-    "audiosync-cli"
+/// Items for files picked in a dialog, read under the network timeout, with
+/// subtitles attached to videos when `options` asks for them.
+async fn picked_items(files: &[PathBuf], file_type: &'static str, options: &ListOptions) -> Result<Vec<FileItem>, String> {
+  let Some(first) = files.first() else {
+    return Ok(Vec::new());
   };
-  
-  // The primary and recommended way to get the sidecar is from the resource directory.
-  if let Some(path) = app.path().resolve(sidecar_name, BaseDirectory::Resource).ok() {
-    if path.exists() {
-      return Some(path);
+  let (files, find_subtitles) = (files.to_vec(), options.find_subtitles && file_type == "video");
+  let read = move || {
+    let mut items: Vec<FileItem> = files.iter().map(|file| file_item(file, file_display_name(file), file_type)).collect();
+    if find_subtitles {
+      attach_subtitles(&mut items);
+    }
+    items
+  };
+  with_network_timeout(first, options, read).await
+}
+
+/// Picks audio files in movie mode and an audio folder in series mode. With
+/// `pick_folder`, movie mode lists a folder too, leaving the user to choose
+/// the file from it.
+#[tauri::command]
+async fn pick_audio_files(
+  window: Window,
+  mode: String,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+  pick_folder: Option<bool>,
+) -> Result<PickResponse, AppError> {
+  let app = window.app_handle().clone();
+  let options = options.unwrap_or_default().with_saved_min_size(&app);
+  options.name_globs().map_err(AppError::InvalidRequest)?;
+  if mode == "movie" && !pick_folder.unwrap_or(false) {
+    let picked = match pick_files_async(window, "audio", &AUDIO_FILTERS).await {
+      Some(picked) if !picked.is_empty() => picked,
+      _ => return Ok(PickResponse::default()),
+    };
+    let files = picked_items(&picked, "audio", &options).await.map_err(AppError::Io)?;
+    let warnings = unrecognized_audio_warnings(app.clone(), picked.clone()).await.map_err(AppError::ProbeFailed)?;
+    let mut files = if options.probe_durations {
+      with_durations(app, files).await.map_err(AppError::ProbeFailed)?
+    } else {
+      files
+    };
+    if let Some(sort) = sort {
+      sort_files(&mut files, sort);
     }
+    let parents: Vec<PathBuf> = picked
+      .iter()
+      .filter_map(|file| file.parent().map(Path::to_path_buf))
+      .collect();
+    let folders = distinct_folders(&parents);
+    return Ok(PickResponse {
+      folder: common_ancestor(&parents).map(|p| p.to_string_lossy().to_string()),
+      folders,
+      files,
+      errors: Vec::new(),
+      warnings,
+      skipped_small: 0,
+      listing_id: None,
+    });
   }
 
-  // Fallback for development, searching relative to the current executable.
-  if let Ok(current_exe) = std::env::current_exe() {
-    if let Some(parent) = current_exe.parent() {
-      let dev_path = parent.join("../../../src-tauri/bin").join(sidecar_name);
-      if dev_path.exists() {
-        return Some(dev_path);
-      }
+  let folder = pick_folder_async(window, "audio").await;
+  let folder = match folder {
+    Some(path) => path,
+    None => {
+      return Ok(PickResponse::default())
     }
+  };
+  if options.stream {
+    return Ok(streamed_response(&app, folder, ListingKind::Audio, options));
   }
-  
-  None
-}
 
-fn find_python_exe() -> Option<PathBuf> {
-  let candidate = if cfg!(windows) {
-    PathBuf::from("python/.venv/Scripts/python.exe")
+  let files = list_guarded(&folder, &options, ListingKind::Audio).await.map_err(AppError::Io)?.files;
+  let mut files = if options.probe_durations {
+    with_durations(app, files).await.map_err(AppError::ProbeFailed)?
   } else {
-    PathBuf::from("python/.venv/bin/python")
+    files
   };
+  if let Some(sort) = sort {
+    sort_files(&mut files, sort);
+  }
+
+  let folder = folder.to_string_lossy().to_string();
+  Ok(PickResponse {
+    folder: Some(folder.clone()),
+    folders: vec![folder],
+    files,
+    errors: Vec::new(),
+    warnings: Vec::new(),
+    skipped_small: 0,
+    listing_id: None,
+  })
+}
+
+/// Which listing helper a streamed listing mirrors.
+#[derive(Debug, Clone, Copy)]
+enum ListingKind {
+  MovieVideos,
+  FolderFiles,
+  Audio,
+}
+
+impl ListingKind {
+  fn accepts(self, path: &Path, options: &ListOptions) -> bool {
+    match self {
+      ListingKind::MovieVideos => is_movie_video(path),
+      ListingKind::FolderFiles => true,
+      ListingKind::Audio => options.is_audio(path),
+    }
+  }
+
+  fn file_type(self) -> &'static str {
+    match self {
+      ListingKind::Audio => "audio",
+      _ => "video",
+    }
+  }
+
+  fn list(self, folder: &Path, options: &ListOptions) -> Listing {
+    match self {
+      ListingKind::MovieVideos => list_movie_videos(folder, options),
+      ListingKind::FolderFiles => list_folder_files(folder, options),
+      ListingKind::Audio => Listing::from(list_audio_files(folder, options)),
+    }
+  }
+}
+
+/// True for UNC shares (`\\server\share`, `\\?\UNC\...`, `//server/share`),
+/// whose filesystem calls can block for a long time once the share is gone.
+fn is_network_path(path: &Path) -> bool {
+  let raw = path.to_string_lossy();
+  raw.starts_with(r"\\?\UNC\")
+    || (raw.starts_with(r"\\") && !raw.starts_with(r"\\?\") && !raw.starts_with(r"\\.\"))
+    || raw.starts_with("//")
+}
+
+/// `//server/share` (lowercased) for a network path, so every path on one
+/// share maps to the same key.
+fn share_root(path: &Path) -> String {
+  let raw = path.to_string_lossy();
+  let rest = raw.strip_prefix(r"\\?\UNC\").unwrap_or_else(|| raw.trim_start_matches(['\\', '/']));
+  let parts: Vec<&str> = rest.split(['\\', '/']).filter(|part| !part.is_empty()).take(2).collect();
+  format!("//{}", parts.join("/")).to_lowercase()
+}
+
+/// Shares with guarded work that outlived the network timeout and hasn't
+/// returned yet, with how many such calls each has.
+fn stalled_shares() -> &'static Mutex<HashMap<String, usize>> {
+  static STALLED: std::sync::OnceLock<Mutex<HashMap<String, usize>>> = std::sync::OnceLock::new();
+  STALLED.get_or_init(Default::default)
+}
+
+/// Runs filesystem `work` for `folder`. On network paths it runs on its own
+/// thread and is given up on after the network timeout, so a dead share
+/// yields an error instead of a hung command. A blocked read can't be
+/// interrupted, so the share is then marked stalled and further work there
+/// fails at once, rather than stranding a thread per call, until the stuck
+/// read returns.
+async fn with_network_timeout<T: Send + 'static>(
+  folder: &Path,
+  options: &ListOptions,
+  work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, String> {
+  if !is_network_path(folder) {
+    return Ok(work());
+  }
+  let share = share_root(folder);
+  if stalled_shares().lock().is_ok_and(|stalled| stalled.contains_key(&share)) {
+    return Err(format!(
+      "Network path unreachable: {} (an earlier read on {share} hasn't returned)",
+      folder.display()
+    ));
+  }
+  let timeout = Duration::from_secs(options.network_timeout_secs.unwrap_or(DEFAULT_NETWORK_TIMEOUT_SECS));
+  // Both flags change only under the `stalled_shares` lock, so a stall is
+  // counted exactly when the work is still running and uncounted once.
+  let done = Arc::new(AtomicBool::new(false));
+  let counted = Arc::new(AtomicBool::new(false));
+  let (tx, rx) = std::sync::mpsc::channel();
+  {
+    let (share, done, counted) = (share.clone(), done.clone(), counted.clone());
+    std::thread::spawn(move || {
+      let result = work();
+      if let Ok(mut stalled) = stalled_shares().lock() {
+        done.store(true, Ordering::SeqCst);
+        if counted.load(Ordering::SeqCst) {
+          if let Some(count) = stalled.get_mut(&share) {
+            *count -= 1;
+            if *count == 0 {
+              stalled.remove(&share);
+            }
+          }
+        }
+      }
+      let _ = tx.send(result);
+    });
+  }
+  let received = tauri::async_runtime::spawn_blocking(move || rx.recv_timeout(timeout))
+    .await
+    .map_err(|err| err.to_string())?;
+  received.map_err(|_| {
+    if let Ok(mut stalled) = stalled_shares().lock() {
+      if !done.load(Ordering::SeqCst) {
+        counted.store(true, Ordering::SeqCst);
+        *stalled.entry(share).or_default() += 1;
+      }
+    }
+    format!(
+      "Network path unreachable: {} (no response within {}s)",
+      folder.display(),
+      timeout.as_secs()
+    )
+  })
+}
+
+/// Lists `folder` like `kind`'s helper, under the network timeout.
+async fn list_guarded(folder: &Path, options: &ListOptions, kind: ListingKind) -> Result<Listing, String> {
+  let (target, listing_options) = (folder.to_path_buf(), options.clone());
+  with_network_timeout(folder, options, move || kind.list(&target, &listing_options)).await
+}
+
+/// Cancellation flags for listings still streaming, by listing id.
+#[derive(Default)]
+struct ListingState {
+  next_id: AtomicU64,
+  active: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+/// Files per `pick-progress` batch.
+const LISTING_BATCH: usize = 250;
+
+/// Threads reading file metadata for a streamed listing.
+const LISTING_WORKERS: usize = 4;
+
+/// Starts a background listing of `folder` and returns a response carrying
+/// only its id; the files follow as `pick-progress` events.
+fn streamed_response(app: &AppHandle, folder: PathBuf, kind: ListingKind, options: ListOptions) -> PickResponse {
+  let folder_name = folder.to_string_lossy().to_string();
+  let listing_id = start_listing(app, folder, kind, options);
+  PickResponse {
+    folder: Some(folder_name.clone()),
+    folders: vec![folder_name],
+    files: Vec::new(),
+    errors: Vec::new(),
+    warnings: Vec::new(),
+    skipped_small: 0,
+    listing_id: Some(listing_id),
+  }
+}
+
+/// Walks `folder` on a worker thread, emitting batches of `FileItem`s with a
+/// running count. The last event has `done: true` along with the
+/// `skipped_small` total and whether the listing was canceled.
+fn start_listing(app: &AppHandle, folder: PathBuf, kind: ListingKind, options: ListOptions) -> u64 {
+  let state = app.state::<ListingState>();
+  let listing_id = state.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+  let cancel = Arc::new(AtomicBool::new(false));
+  if let Ok(mut active) = state.active.lock() {
+    active.insert(listing_id, cancel.clone());
+  }
+
+  let app = app.clone();
+  std::thread::spawn(move || {
+    let paths: Vec<(PathBuf, String)> = collect_files(&folder, &options)
+      .into_iter()
+      .filter(|(path, _)| kind.accepts(path, &options))
+      .collect();
+    let total = paths.len();
+    let mut count = 0;
+    let mut skipped_small = 0;
+    for chunk in paths.chunks(LISTING_BATCH) {
+      if cancel.load(Ordering::SeqCst) {
+        break;
+      }
+      let items = parallel_map(chunk, LISTING_WORKERS, |(path, name)| file_item(path, name.clone(), kind.file_type()));
+      let mut batch = Listing::default();
+      for item in items {
+        // Like list_audio_files, audio listings ignore `min_size_bytes`.
+        match kind {
+          ListingKind::Audio => batch.files.push(item),
+          _ => batch.push_sized(item, &options),
+        }
+      }
+      if options.find_subtitles && !matches!(kind, ListingKind::Audio) {
+        attach_subtitles(&mut batch.files);
+      }
+      count += batch.files.len();
+      skipped_small += batch.skipped_small;
+      let _ = app.emit(
+        "pick-progress",
+        serde_json::json!({
+          "listing_id": listing_id,
+          "items": batch.files,
+          "count": count,
+          "total": total,
+          "done": false,
+        }),
+      );
+    }
+
+    if let Ok(mut active) = app.state::<ListingState>().active.lock() {
+      active.remove(&listing_id);
+    }
+    let _ = app.emit(
+      "pick-progress",
+      serde_json::json!({
+        "listing_id": listing_id,
+        "items": [],
+        "count": count,
+        "total": total,
+        "skipped_small": skipped_small,
+        "done": true,
+        "canceled": cancel.load(Ordering::SeqCst),
+      }),
+    );
+  });
+  listing_id
+}
+
+/// Stops a streamed listing; unknown or finished ids are ignored.
+#[tauri::command]
+fn cancel_listing(state: State<'_, ListingState>, listing_id: u64) -> Result<(), AppError> {
+  let active = state.active.lock().map_err(|err| AppError::Other(err.to_string()))?;
+  if let Some(cancel) = active.get(&listing_id) {
+    cancel.store(true, Ordering::SeqCst);
+  }
+  Ok(())
+}
+
+/// Probes files picked through the "All files" filter and warns about any
+/// without an audio stream; recognised audio extensions are trusted as-is.
+async fn unrecognized_audio_warnings(app: AppHandle, picked: Vec<PathBuf>) -> Result<Vec<PathError>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let ffprobe_path = resolve_ffprobe(&app);
+    picked
+      .iter()
+      .filter(|file| !has_extension(file, &AUDIO_EXTENSIONS))
+      .filter_map(|file| {
+        let path = file.to_string_lossy().to_string();
+        let probed = ffprobe_path
+          .as_ref()
+          .map_err(Clone::clone)
+          .and_then(|ffprobe_path| probe_cached(&app, ffprobe_path, &path, None));
+        let message = match probed {
+          Ok(probe) if probe.has_audio => return None,
+          Ok(_) => "No audio stream found".to_string(),
+          Err(err) => format!("Could not probe file: {err}"),
+        };
+        Some(PathError { path, message })
+      })
+      .collect()
+  })
+  .await
+  .map_err(|err| err.to_string())
+}
+
+/// Turns paths dropped onto the window into a listing shaped like the pickers'.
+///
+/// `role` is `"video"` or `"audio"`; dropped folders are expanded with the
+/// matching listing helper and bad paths are collected in `errors`.
+#[tauri::command]
+async fn register_dropped_paths(
+  app: AppHandle,
+  paths: Vec<String>,
+  role: String,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  let kind = match role.as_str() {
+    "video" => ListingKind::MovieVideos,
+    "audio" => ListingKind::Audio,
+    _ => return Err(AppError::InvalidRequest(format!("Unknown role: {role}"))),
+  };
+  ingest_paths(app, paths, kind, options, sort).await
+}
+
+/// Lists already-resolved video paths (folders and/or files) without a
+/// dialog. `mode` picks the folder listing as `pick_video_files` does.
+#[tauri::command]
+async fn ingest_video_paths(
+  app: AppHandle,
+  paths: Vec<String>,
+  mode: String,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  let kind = if mode == "series" { ListingKind::FolderFiles } else { ListingKind::MovieVideos };
+  ingest_paths(app, paths, kind, options, sort).await
+}
+
+/// Lists already-resolved audio paths (folders and/or files) without a
+/// dialog. Both modes list audio the same way, so unlike
+/// `ingest_video_paths` it takes no `mode`.
+#[tauri::command]
+async fn ingest_audio_paths(
+  app: AppHandle,
+  paths: Vec<String>,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  ingest_paths(app, paths, ListingKind::Audio, options, sort).await
+}
+
+/// Shared body of the drop and ingest commands: folders are listed as `kind`,
+/// and files go through the same role, name glob, hidden file and minimum
+/// size filters a listing applies. Paths that no longer exist become
+/// `warnings`; other failures go to `errors`.
+async fn ingest_paths(
+  app: AppHandle,
+  paths: Vec<String>,
+  kind: ListingKind,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  let options = options.unwrap_or_default().with_saved_min_size(&app);
+  let globs = options.name_globs().map_err(AppError::InvalidRequest)?;
+
+  let mut response = PickResponse::default();
+  let mut folders = Vec::new();
+  let mut seen_folders = HashSet::new();
+  for raw in paths {
+    let path = PathBuf::from(&raw);
+    let target = path.clone();
+    // A folder must also be readable; checked in the same guarded call.
+    let read = move || {
+      let meta = fs::metadata(&target)?;
+      if meta.is_dir() {
+        fs::read_dir(&target)?;
+      }
+      Ok::<_, std::io::Error>(meta)
+    };
+    let meta = match with_network_timeout(&path, &options, read).await {
+      Ok(Ok(meta)) => meta,
+      Ok(Err(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+        response.warnings.push(PathError {
+          path: raw,
+          message: "Path not found".to_string(),
+        });
+        continue;
+      }
+      Ok(Err(err)) => {
+        response.errors.push(PathError { path: raw, message: err.to_string() });
+        continue;
+      }
+      Err(message) => {
+        response.errors.push(PathError { path: raw, message });
+        continue;
+      }
+    };
+    if meta.is_dir() {
+      let listed = match list_guarded(&path, &options, kind).await {
+        Ok(listed) => listed,
+        Err(message) => {
+          response.errors.push(PathError { path: raw, message });
+          continue;
+        }
+      };
+      response.files.extend(listed.files);
+      response.skipped_small += listed.skipped_small;
+      if seen_folders.insert(path.clone()) {
+        folders.push(path);
+      }
+    } else {
+      let name = file_display_name(&path);
+      let accepted = match kind {
+        ListingKind::FolderFiles => media_kind(&path) == Some("video"),
+        _ => kind.accepts(&path, &options),
+      };
+      if !accepted {
+        response.errors.push(PathError {
+          path: raw,
+          message: format!("Not a supported {} file", kind.file_type()),
+        });
+        continue;
+      }
+      if !globs.matches(&name) {
+        continue;
+      }
+      let (target, include_hidden, file_type) = (path.clone(), options.include_hidden, kind.file_type());
+      let read = move || {
+        let hidden = !include_hidden && is_hidden_or_junk(|| fs::symlink_metadata(&target).ok(), &name);
+        (!hidden).then(|| file_item(&target, name, file_type))
+      };
+      let item = match with_network_timeout(&path, &options, read).await {
+        Ok(Some(item)) => item,
+        Ok(None) => continue,
+        Err(message) => {
+          response.errors.push(PathError { path: raw, message });
+          continue;
+        }
+      };
+      let before = response.files.len();
+      // Like listings, audio ignores `min_size_bytes`.
+      match kind {
+        ListingKind::Audio => response.files.push(item),
+        _ => {
+          let mut listing = Listing::default();
+          listing.push_sized(item, &options);
+          response.skipped_small += listing.skipped_small;
+          response.files.extend(listing.files);
+        }
+      }
+      if response.files.len() > before {
+        if let Some(parent) = path.parent().filter(|parent| seen_folders.insert(parent.to_path_buf())) {
+          folders.push(parent.to_path_buf());
+        }
+      }
+    }
+  }
+
+  response.folder = common_ancestor(&folders).map(|p| p.to_string_lossy().to_string());
+  response.folders = folders.iter().map(|p| p.to_string_lossy().to_string()).collect();
+  if options.probe_durations {
+    response.files = with_durations(app, response.files).await.map_err(AppError::ProbeFailed)?;
+  }
+  if let Some(sort) = sort {
+    sort_files(&mut response.files, sort);
+  }
+  Ok(response)
+}
+
+/// Re-lists a previously picked folder without opening a dialog.
+///
+/// `role` is `"video"` or `"audio"`; `mode` picks the video listing the same
+/// way `pick_video_files` does and defaults to `"movie"`.
+#[tauri::command]
+async fn rescan_folder(
+  app: AppHandle,
+  path: String,
+  role: String,
+  mode: Option<String>,
+  options: Option<ListOptions>,
+  sort: Option<SortOptions>,
+) -> Result<PickResponse, AppError> {
+  let options = options.unwrap_or_default().with_saved_min_size(&app);
+  options.name_globs().map_err(AppError::InvalidRequest)?;
+  let kind = match (role.as_str(), mode.as_deref().unwrap_or("movie")) {
+    ("video", "series") => ListingKind::FolderFiles,
+    ("video", _) => ListingKind::MovieVideos,
+    ("audio", _) => ListingKind::Audio,
+    _ => return Err(AppError::InvalidRequest(format!("Unknown role: {role}"))),
+  };
+  let folder = PathBuf::from(&path);
+  let (target, shown) = (folder.clone(), path.clone());
+  with_network_timeout(&folder, &options, move || check_folder(&target, &shown))
+    .await
+    .and_then(|checked| checked)
+    .map_err(AppError::Io)?;
+  if options.stream {
+    return Ok(streamed_response(&app, folder, kind, options));
+  }
+
+  let Listing { files, skipped_small } = list_guarded(&folder, &options, kind).await.map_err(AppError::Io)?;
+  let mut files = if options.probe_durations {
+    with_durations(app, files).await.map_err(AppError::ProbeFailed)?
+  } else {
+    files
+  };
+  if let Some(sort) = sort {
+    sort_files(&mut files, sort);
+  }
+
+  Ok(PickResponse {
+    folder: Some(path.clone()),
+    folders: vec![path],
+    files,
+    errors: Vec::new(),
+    warnings: Vec::new(),
+    skipped_small,
+    listing_id: None,
+  })
+}
+
+/// Confirms `folder` is an existing, readable directory.
+fn check_folder(folder: &Path, path: &str) -> Result<(), String> {
+  match fs::metadata(folder) {
+    Ok(meta) if meta.is_dir() => {}
+    Ok(_) => return Err(format!("Not a folder: {path}")),
+    Err(err) => return Err(format!("Folder unavailable: {path} ({err})")),
+  }
+  fs::read_dir(folder).map_err(|err| format!("Folder unreadable: {path} ({err})"))?;
+  Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct SiblingPair {
+  video: FileItem,
+  audio: Vec<FileItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct SiblingAudio {
+  pairs: Vec<SiblingPair>,
+  /// Videos with no audio file sharing their stem.
+  unmatched: Vec<FileItem>,
+}
+
+/// Proposes video/audio pairs for releases that keep external audio next to
+/// the video, e.g. `S01E01.mkv` with `S01E01.ger.mka`.
+///
+/// An audio file belongs to the video whose stem is its longest
+/// case-insensitive prefix ending at a separator, so language or flag
+/// suffixes like `.ger`, `_eng` or `-commentary` are ignored.
+#[tauri::command]
+fn detect_sibling_audio(video_folder: String) -> Result<SiblingAudio, AppError> {
+  let folder = PathBuf::from(&video_folder);
+  if !folder.is_dir() {
+    return Err(AppError::Io(format!("Not a folder: {video_folder}")));
+  }
+  let options = ListOptions::default();
+  let videos = list_movie_videos(&folder, &options).files;
+  let stems: Vec<String> = videos.iter().map(|video| file_stem_lower(&video.path)).collect();
+
+  let mut matched: Vec<Vec<FileItem>> = videos.iter().map(|_| Vec::new()).collect();
+  for audio in list_audio_files(&folder, &options) {
+    let audio_stem = file_stem_lower(&audio.path);
+    let best = stems
+      .iter()
+      .enumerate()
+      .filter(|(_, stem)| is_sibling_stem(&audio_stem, stem))
+      .max_by_key(|(_, stem)| stem.len());
+    if let Some((index, _)) = best {
+      matched[index].push(audio);
+    }
+  }
+
+  let mut response = SiblingAudio { pairs: Vec::new(), unmatched: Vec::new() };
+  for (video, audio) in videos.into_iter().zip(matched) {
+    if audio.is_empty() {
+      response.unmatched.push(video);
+    } else {
+      response.pairs.push(SiblingPair { video, audio });
+    }
+  }
+  Ok(response)
+}
+
+fn file_stem_lower(path: &str) -> String {
+  Path::new(path)
+    .file_stem()
+    .map(|stem| nfc(&stem.to_string_lossy()).to_lowercase())
+    .unwrap_or_default()
+}
+
+/// True when `audio_stem` is `video_stem`, optionally followed by a suffix
+/// that starts with a separator.
+fn is_sibling_stem(audio_stem: &str, video_stem: &str) -> bool {
+  match audio_stem.strip_prefix(video_stem) {
+    Some(rest) => rest.is_empty() || rest.starts_with(['.', '_', '-', ' ']),
+    None => false,
+  }
+}
+
+/// The checks every way of starting a sync runs before anything is queued:
+/// the request's own validation, then a bridge probe for a named algorithm.
+async fn check_request(app: &AppHandle, request: &SyncRequest) -> Result<(), AppError> {
+  request.validate().map_err(AppError::InvalidRequest)?;
+  if let Some(algorithm) = request.algorithm.clone() {
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || check_algorithm(&handle, &algorithm))
+      .await
+      .map_err(|err| AppError::Other(err.to_string()))??;
+  }
+  Ok(())
+}
+
+#[tauri::command]
+async fn start_sync(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  request: SyncRequest,
+) -> Result<Vec<SyncResult>, AppError> {
+  check_request(&app, &request).await?;
+  // Events of this run carry the id, so callers that need to cancel it
+  // precisely can pass their own or pick it up from the first event.
+  let id = state.claim_job_id(request.job_id)?;
+  let cancel = Arc::new(AtomicBool::new(false));
+  {
+    let mut running = state.running.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    if running.contains_key(&id) {
+      return Err(AppError::InvalidRequest(format!("Job {id} is already running")));
+    }
+    running.insert(id, cancel.clone());
+  }
+  if let Some(folder) = request_video_folders(&request).into_iter().next() {
+    if let Err(err) = update_settings(&app, |settings| settings.last_sync_video_folder = Some(folder)) {
+      log::warn!("Failed to remember last sync folder: {err}");
+    }
+  }
+  let handle = app.clone();
+  let job = SyncRequest { job_id: None, ..request.clone() };
+  let request = SyncRequest { job_id: Some(id), ..request };
+  let token = cancel.clone();
+  let outcome = tauri::async_runtime::spawn_blocking(move || run_bridge(handle, request, token)).await;
+  if let Ok(mut running) = state.running.lock() {
+    running.remove(&id);
+  }
+  let results = outcome.map_err(|err| AppError::Other(err.to_string()))??;
+  if !job.dry_run && !cancel.load(Ordering::SeqCst) {
+    if let Err(err) = remember_job(&app, job) {
+      log::warn!("Failed to remember sync job: {err}");
+    }
+  }
+  Ok(results)
+}
+
+/// Puts `request` at the front of the recent jobs, dropping an identical
+/// older entry and anything past `MAX_RECENT_JOBS`.
+fn remember_job(app: &AppHandle, request: SyncRequest) -> Result<(), String> {
+  let key = serde_json::to_value(&request).map_err(|err| err.to_string())?;
+  let finished_at = unix_millis(SystemTime::now()).unwrap_or_default();
+  update_settings(app, |settings| {
+    settings
+      .recent_jobs
+      .retain(|job| serde_json::to_value(&job.request).ok().as_ref() != Some(&key));
+    settings.recent_jobs.insert(0, RecentJob { request, finished_at });
+    settings.recent_jobs.truncate(MAX_RECENT_JOBS);
+  })
+}
+
+#[derive(Debug, Serialize)]
+struct RecentJobInfo {
+  request: SyncRequest,
+  finished_at: u64,
+  /// Folders and files of the request that no longer exist, or sit on a
+  /// network share that didn't answer within the network timeout.
+  missing: Vec<String>,
+  /// Set when anything is missing; the UI grays these out.
+  stale: bool,
+}
+
+/// Every folder and file a request reads from.
+fn request_inputs(request: &SyncRequest) -> Vec<String> {
+  let mut inputs = request_video_folders(request);
+  inputs.extend(request.audio_folder.iter().cloned());
+  inputs.extend(request.audio_file.iter().flatten().cloned());
+  inputs.extend(request.video_files.iter().flatten().cloned());
+  inputs.extend(
+    request
+      .pairs
+      .iter()
+      .flatten()
+      .flat_map(|(video, audio)| [video.clone(), audio.clone()]),
+  );
+  inputs
+}
+
+#[tauri::command]
+async fn list_recent_jobs(app: AppHandle) -> Vec<RecentJobInfo> {
+  let options = ListOptions::default();
+  let mut jobs = Vec::new();
+  for job in load_settings_file(&app).recent_jobs {
+    let mut missing = Vec::new();
+    for path in request_inputs(&job.request) {
+      let target = PathBuf::from(&path);
+      let exists = with_network_timeout(Path::new(&path), &options, move || target.exists()).await;
+      if !exists.unwrap_or(false) {
+        missing.push(path);
+      }
+    }
+    jobs.push(RecentJobInfo {
+      stale: !missing.is_empty(),
+      request: job.request,
+      finished_at: job.finished_at,
+      missing,
+    });
+  }
+  jobs
+}
+
+/// Runs recent job `index` (0 is the newest) again through `start_sync`.
+#[tauri::command]
+async fn rerun_job(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  index: usize,
+) -> Result<Vec<SyncResult>, AppError> {
+  let job = load_settings_file(&app)
+    .recent_jobs
+    .into_iter()
+    .nth(index)
+    .ok_or_else(|| AppError::InvalidRequest(format!("No recent job at index {index}")))?;
+  start_sync(app, state, job.request).await
+}
+
+/// The header and rows `export_csv` and `results_to_clipboard` write.
+fn results_table(results: &[SyncResult]) -> Vec<Vec<String>> {
+  // One column per anchor position any result measured.
+  let mut positions: Vec<f64> = results.iter().flat_map(|result| result.delays.iter().map(|delay| delay.position)).collect();
+  positions.sort_by(f64::total_cmp);
+  positions.dedup();
+
+  let header = ["Video", "Audio", "Source", "Start Delay (ms)", "End Delay (ms)"]
+    .into_iter()
+    .map(str::to_string)
+    .chain(positions.iter().map(|position| format!("Delay @ {}% (ms)", percent_label(*position))))
+    .chain(
+      [
+        "Correlation Confidence",
+        "Drift (ms/min)",
+        "Speed Factor",
+        "Speed Mismatch",
+        "Gain (dB)",
+        "Input Loudness (LUFS)",
+        "Elapsed (ms)",
+        "Sample Rate (Hz)",
+        "Algorithm",
+        "Video Stream",
+        "Audio Stream",
+        "Error",
+        "Status",
+      ]
+      .map(str::to_string),
+    )
+    .collect();
+  let mut table = vec![header];
+  for result in results {
+    let anchors = positions.iter().map(|position| {
+      result
+        .delays
+        .iter()
+        .find(|delay| delay.position == *position)
+        .and_then(|delay| delay.delay_ms)
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+    });
+    let err = result.error.clone().unwrap_or_default();
+    let status = match result.status {
+      Some(ResultStatus::Skipped) => "skipped",
+      Some(ResultStatus::Unmatched) => "unmatched",
+      Some(ResultStatus::Warning) => "warning",
+      Some(ResultStatus::Planned) => "planned",
+      Some(ResultStatus::LowConfidence) => "low_confidence",
+      Some(ResultStatus::TimedOut) => "timed_out",
+      None if err.is_empty() => "ok",
+      None => "error",
+    };
+    let row = [
+      result.videoFile.clone(),
+      result.audioFile.clone(),
+      result.source_label.clone().unwrap_or_default(),
+      result.startDelay.map(|v| v.to_string()).unwrap_or_default(),
+      result.endDelay.map(|v| v.to_string()).unwrap_or_default(),
+    ]
+    .into_iter()
+    .chain(anchors)
+    .chain([
+      result.correlation_confidence.map(|v| format!("{v:.3}")).unwrap_or_default(),
+      result.drift_ms_per_min.map(|v| format!("{v:.3}")).unwrap_or_default(),
+      result.speed_factor.map(|v| format!("{v:.5}")).unwrap_or_default(),
+      result.speed_mismatch.as_ref().map(SpeedMismatch::describe).unwrap_or_default(),
+      result.gain_db.map(|v| format!("{v:.2}")).unwrap_or_default(),
+      result.input_lufs.map(|v| format!("{v:.2}")).unwrap_or_default(),
+      result.elapsedMs.map(|v| v.to_string()).unwrap_or_default(),
+      result.sample_rate.map(|v| v.to_string()).unwrap_or_default(),
+      result.algorithm.clone().unwrap_or_default(),
+      result.video_stream.map(|v| v.to_string()).unwrap_or_default(),
+      result.audio_stream.map(|v| v.to_string()).unwrap_or_default(),
+      err,
+      status.to_string(),
+    ])
+    .collect();
+    table.push(row);
+  }
+  table
+}
+
+#[tauri::command]
+async fn export_csv(window: Window, results: Vec<SyncResult>) -> Result<String, AppError> {
+  let path = save_file_async(window, "sync-results.csv", ("CSV", &["csv"])).await;
+  let Some(path) = path else {
+    return Err(AppError::Canceled);
+  };
+
+  let mut csv = String::new();
+  for row in results_table(&results) {
+    csv.push_str(&row.iter().map(|field| csv_escape(field)).collect::<Vec<_>>().join(","));
+    csv.push('\n');
+  }
+
+  fs::write(&path, csv.as_bytes())?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+/// Integrated loudness (LUFS) the bridge's `normalize` aims for.
+const LOUDNORM_TARGET_LUFS: f64 = -23.0;
+
+/// Writes `results` with a `metadata` block recording the app version and
+/// whether the audio was loudness-normalized, and to what target.
+#[tauri::command]
+async fn export_json(window: Window, results: Vec<SyncResult>) -> Result<String, AppError> {
+  let version = window.app_handle().package_info().version.to_string();
+  let path = save_file_async(window, "sync-results.json", ("JSON", &["json"])).await;
+  let Some(path) = path else {
+    return Err(AppError::Canceled);
+  };
+
+  let normalized = results.iter().any(|result| result.input_lufs.is_some());
+  let export = serde_json::json!({
+    "metadata": {
+      "version": version,
+      "normalized": normalized,
+      "loudnessTargetLufs": normalized.then_some(LOUDNORM_TARGET_LUFS),
+    },
+    "results": results,
+  });
+  let json = serde_json::to_string_pretty(&export).map_err(|err| AppError::Other(err.to_string()))?;
+  fs::write(&path, json.as_bytes())?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+/// Copies `results` to the clipboard as tab-separated text with the
+/// `export_csv` columns. Fails on an empty list so there's nothing to paste.
+#[tauri::command]
+fn results_to_clipboard(app: AppHandle, results: Vec<SyncResult>) -> Result<(), AppError> {
+  if results.is_empty() {
+    return Err(AppError::InvalidRequest("No results to copy".to_string()));
+  }
+  let tsv: Vec<String> = results_table(&results)
+    .iter()
+    .map(|row| row.iter().map(|field| tsv_field(field)).collect::<Vec<_>>().join("\t"))
+    .collect();
+  app.clipboard().write_text(tsv.join("\n")).map_err(|err| AppError::Other(err.to_string()))
+}
+
+/// TSV has no quoting, so tabs and line breaks inside a field become spaces.
+fn tsv_field(field: &str) -> String {
+  field.replace(['\t', '\r', '\n'], " ")
+}
+
+/// Escapes a CSV field per RFC 4180: fields containing commas, quotes or line
+/// breaks are quoted, and embedded quotes are doubled.
+fn csv_escape(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+/// A folder listing plus the number of files dropped by `min_size_bytes`.
+#[derive(Debug, Default)]
+struct Listing {
+  files: Vec<FileItem>,
+  skipped_small: usize,
+}
+
+impl From<Vec<FileItem>> for Listing {
+  fn from(files: Vec<FileItem>) -> Self {
+    Self { files, skipped_small: 0 }
+  }
+}
+
+impl Listing {
+  /// Keeps `item` unless it is smaller than the configured minimum.
+  fn push_sized(&mut self, item: FileItem, options: &ListOptions) {
+    let min_size = options.min_size_bytes.unwrap_or(0);
+    if min_size > 0 && item.size.is_some_and(|size| size < min_size) {
+      self.skipped_small += 1;
+    } else {
+      self.files.push(item);
+    }
+  }
+}
+
+/// Whether movie mode treats `path` as a video.
+fn is_movie_video(path: &Path) -> bool {
+  has_extension(path, &VIDEO_EXTENSIONS)
+}
+
+fn list_movie_videos(folder: &Path, options: &ListOptions) -> Listing {
+  let mut listing = Listing::default();
+  for (path, name) in collect_files(folder, options) {
+    if !is_movie_video(&path) {
+      continue;
+    }
+    listing.push_sized(file_item(&path, name, "video"), options);
+  }
+  listing.files.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+  if options.find_subtitles {
+    attach_subtitles(&mut listing.files);
+  }
+  listing
+}
+
+fn list_audio_files(folder: &Path, options: &ListOptions) -> Vec<FileItem> {
+  let mut items = Vec::new();
+  for (path, name) in collect_files(folder, options) {
+    if !options.is_audio(&path) {
+      continue;
+    }
+    items.push(file_item(&path, name, "audio"));
+  }
+  items.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+  items
+}
+
+fn file_item(path: &Path, name: String, file_type: &str) -> FileItem {
+  let meta = fs::metadata(path).ok();
+  FileItem {
+    name,
+    path: path.to_string_lossy().to_string(),
+    file_type: file_type.to_string(),
+    size: meta.as_ref().map(|meta| meta.len()),
+    duration: None,
+    modified: meta.as_ref().and_then(|meta| meta.modified().ok()).and_then(unix_millis),
+    created: meta.as_ref().and_then(|meta| meta.created().ok()).and_then(unix_millis),
+    subtitles: Vec::new(),
+  }
+}
+
+fn unix_millis(time: SystemTime) -> Option<u64> {
+  time
+    .duration_since(UNIX_EPOCH)
+    .ok()
+    .map(|elapsed| elapsed.as_millis() as u64)
+}
+
+fn file_display_name(path: &Path) -> String {
+  path
+    .file_name()
+    .map(|s| nfc(&s.to_string_lossy()))
+    .unwrap_or_default()
+}
+
+/// Composes a name to NFC so names that macOS lists decomposed (NFD) compare
+/// equal to the same names written elsewhere. Only display and comparison
+/// use this; `FileItem.path` keeps the on-disk bytes.
+fn nfc(name: &str) -> String {
+  use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+  if is_nfc_quick(name.chars()) == IsNormalized::Yes {
+    name.to_string()
+  } else {
+    name.nfc().collect()
+  }
+}
+
+/// Classifies a file as `"video"` or `"audio"` by extension.
+fn media_kind(path: &Path) -> Option<&'static str> {
+  if has_extension(path, &VIDEO_EXTENSIONS) {
+    Some("video")
+  } else if has_extension(path, &AUDIO_EXTENSIONS) {
+    Some("audio")
+  } else {
+    None
+  }
+}
+
+/// Whether `path` counts as audio under `extensions`, matched
+/// case-insensitively with or without the dot; `AUDIO_EXTENSIONS` plus the
+/// video containers when unset.
+fn accepts_audio(path: &Path, extensions: Option<&[String]>) -> bool {
+  let Some(extensions) = extensions else {
+    return has_extension(path, &AUDIO_EXTENSIONS) || has_extension(path, &VIDEO_EXTENSIONS);
+  };
+  let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+  extensions.iter().any(|allowed| allowed.trim_start_matches('.').to_lowercase() == ext)
+}
+
+fn has_extension(path: &Path, exts: &[&str]) -> bool {
+  let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+  exts.contains(&ext.as_str())
+}
+
+fn list_folder_files(folder: &Path, options: &ListOptions) -> Listing {
+  let mut listing = Listing::default();
+  for (path, name) in collect_files(folder, options) {
+    listing.push_sized(file_item(&path, name, "video"), options);
+  }
+  listing.files.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+  if options.find_subtitles {
+    attach_subtitles(&mut listing.files);
+  }
+  listing
+}
+
+/// Fills `subtitles` for the video files among `files` with the subtitle
+/// files in the same folder named after them: the video's stem itself or
+/// followed by a dot, as in `movie.srt` and `movie.en.forced.ass`.
+fn attach_subtitles(files: &mut [FileItem]) {
+  let mut folders: HashMap<PathBuf, Vec<(String, String)>> = HashMap::new();
+  for file in files.iter_mut() {
+    let path = Path::new(&file.path);
+    if !has_extension(path, &VIDEO_EXTENSIONS) {
+      continue;
+    }
+    let Some(folder) = path.parent() else {
+      continue;
+    };
+    let subtitles = folders.entry(folder.to_path_buf()).or_insert_with(|| {
+      let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+      };
+      entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && has_extension(path, &SUBTITLE_EXTENSIONS))
+        .map(|path| (file_stem_lower(&path.to_string_lossy()), path.to_string_lossy().to_string()))
+        .collect()
+    });
+    let stem = file_stem_lower(&file.path);
+    let prefix = format!("{stem}.");
+    file.subtitles = subtitles
+      .iter()
+      .filter(|(sub_stem, _)| *sub_stem == stem || sub_stem.starts_with(&prefix))
+      .map(|(_, path)| path.clone())
+      .collect();
+    file.subtitles.sort_by(|a, b| natural_cmp(a, b));
+  }
+}
+
+/// `metadata` is only read on Windows, for the hidden attribute.
+fn is_hidden_or_junk(metadata: impl FnOnce() -> Option<fs::Metadata>, file_name: &str) -> bool {
+  if file_name.starts_with('.') || JUNK_FILE_NAMES.contains(&file_name.to_lowercase().as_str()) {
+    return true;
+  }
+
+  #[cfg(windows)]
+  {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    if let Some(meta) = metadata() {
+      if meta.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0 {
+        return true;
+      }
+    }
+  }
+  #[cfg(not(windows))]
+  let _ = metadata;
+
+  false
+}
+
+/// Orders a listing by `sort`, breaking ties by path so output is stable.
+fn sort_files(files: &mut [FileItem], sort: SortOptions) {
+  files.sort_by(|a, b| {
+    let ordering = match sort.by {
+      SortKey::Name => natural_cmp(&a.name, &b.name),
+      SortKey::Size => a.size.cmp(&b.size),
+      SortKey::Modified => a.modified.cmp(&b.modified),
+    };
+    let ordering = if sort.order == SortOrder::Desc {
+      ordering.reverse()
+    } else {
+      ordering
+    };
+    ordering.then_with(|| a.path.cmp(&b.path))
+  });
+}
+
+/// Compares names the way a person would: digit runs by numeric value and
+/// letters case-insensitively, so `S1E9` sorts before `S1E10`.
+fn natural_cmp(a: &str, b: &str) -> CmpOrdering {
+  let mut left = a.chars().peekable();
+  let mut right = b.chars().peekable();
+  loop {
+    match (left.peek().copied(), right.peek().copied()) {
+      (None, None) => break,
+      (None, Some(_)) => return CmpOrdering::Less,
+      (Some(_), None) => return CmpOrdering::Greater,
+      (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+        let left_run = take_digit_run(&mut left);
+        let right_run = take_digit_run(&mut right);
+        let ordering = cmp_digit_runs(&left_run, &right_run);
+        if ordering != CmpOrdering::Equal {
+          return ordering;
+        }
+      }
+      (Some(l), Some(r)) => {
+        let ordering = l.to_lowercase().cmp(r.to_lowercase());
+        if ordering != CmpOrdering::Equal {
+          return ordering;
+        }
+        left.next();
+        right.next();
+      }
+    }
+  }
+  // Names equal up to case still need a stable order.
+  a.cmp(b)
+}
+
+fn take_digit_run(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+  let mut run = String::new();
+  while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+    run.push(c);
+  }
+  run
+}
+
+fn cmp_digit_runs(a: &str, b: &str) -> CmpOrdering {
+  let a_value = a.trim_start_matches('0');
+  let b_value = b.trim_start_matches('0');
+  a_value
+    .len()
+    .cmp(&b_value.len())
+    .then_with(|| a_value.cmp(b_value))
+    .then_with(|| a.len().cmp(&b.len()))
+}
+
+/// Collects regular files under `folder` as `(path, display name)` pairs.
+///
+/// The display name is the path relative to `folder` joined with `/`, so
+/// `Season 01/S01E01.mkv` and `Season 02/S01E01.mkv` stay distinguishable.
+fn collect_files(folder: &Path, options: &ListOptions) -> Vec<(PathBuf, String)> {
+  let mut files = Vec::new();
+  let mut visited = HashSet::new();
+  walk_dir(folder, "", 0, options, &mut visited, &mut files);
+  // Commands validate the globs up front, so a bad pattern can't reach here.
+  let globs = options.name_globs().unwrap_or_default();
+  files.retain(|(_, name)| globs.matches(name.rsplit('/').next().unwrap_or(name)));
+  files
+}
+
+fn walk_dir(
+  dir: &Path,
+  prefix: &str,
+  depth: usize,
+  options: &ListOptions,
+  visited: &mut HashSet<PathBuf>,
+  files: &mut Vec<(PathBuf, String)>,
+) {
+  // Canonical paths catch symlinked directories that loop back on themselves.
+  if let Ok(canonical) = fs::canonicalize(dir) {
+    if !visited.insert(canonical) {
+      return;
+    }
+  }
+
+  let Ok(entries) = fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let path = entry.path();
+    let file_name = nfc(&entry.file_name().to_string_lossy());
+    if !options.include_hidden && is_hidden_or_junk(|| entry.metadata().ok(), &file_name) {
+      continue;
+    }
+    let name = if prefix.is_empty() {
+      file_name
+    } else {
+      format!("{prefix}/{file_name}")
+    };
+    let Ok(file_type) = entry.file_type() else {
+      continue;
+    };
+    // Symlinks (and junctions on Windows) are resolved explicitly: the listed
+    // path is the target while the display name stays the link's own.
+    let (path, meta) = if file_type.is_symlink() {
+      match fs::canonicalize(&path).and_then(|target| fs::metadata(&target).map(|meta| (target, meta))) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+          log::warn!("Skipping broken symlink {}: {err}", path.display());
+          continue;
+        }
+      }
+    } else {
+      match fs::symlink_metadata(&path) {
+        Ok(meta) => (path, meta),
+        Err(_) => continue,
+      }
+    };
+
+    if meta.is_dir() {
+      if depth < options.effective_depth() && (!file_type.is_symlink() || options.follow_symlinks) {
+        walk_dir(&path, &name, depth + 1, options, visited, files);
+      }
+      continue;
+    }
+    if meta.is_file() {
+      files.push((path, name));
+    }
+  }
+}
+
+/// Sidecar file names to look for: Tauri's `<name>-<target triple>` form
+/// first, then the bare name the bundler installs.
+fn sidecar_names() -> Vec<String> {
+  let exe = std::env::consts::EXE_SUFFIX;
+  vec![
+    format!("audiosync-cli-{}{exe}", env!("TARGET_TRIPLE")),
+    format!("audiosync-cli{exe}"),
+  ]
+}
+
+fn find_sidecar_path(app: &AppHandle) -> Option<PathBuf> {
+  let names = sidecar_names();
+
+  // The primary and recommended way to get the sidecar is from the resource directory.
+  let resources = names
+    .iter()
+    .filter_map(|name| app.path().resolve(name, BaseDirectory::Resource).ok());
+  // Bundles install external binaries next to the app executable.
+  let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+  let installed = exe_dir.iter().flat_map(|dir| names.iter().map(move |name| dir.join(name)));
+  // Fallback for development, searching relative to the current executable.
+  let dev = exe_dir
+    .iter()
+    .flat_map(|dir| names.iter().map(move |name| dir.join("../../../src-tauri/bin").join(name)));
+
+  resources.chain(installed).chain(dev).find(|path| path.is_file())
+}
+
+fn find_python_exe() -> Option<PathBuf> {
+  let candidate = if cfg!(windows) {
+    PathBuf::from("python/.venv/Scripts/python.exe")
+  } else {
+    PathBuf::from("python/.venv/bin/python")
+  };
+
+  if candidate.exists() {
+    Some(candidate)
+  } else {
+    None
+  }
+}
+
+/// Overrides the bridge script, e.g. with `python/tree_kill_harness.py`.
+const BRIDGE_ENV: &str = "AUDIOSYNC_BRIDGE";
+
+fn bridge_override() -> Option<PathBuf> {
+  std::env::var_os(BRIDGE_ENV).map(PathBuf::from).filter(|path| path.is_file())
+}
+
+fn find_bridge_path() -> Option<PathBuf> {
+  let candidate = PathBuf::from("python/bridge.py");
+  if candidate.exists() {
+    Some(candidate)
+  } else {
+    None
+  }
+}
+
+/// Queues `request` to run after the jobs ahead of it and returns its id.
+/// Progress arrives as the usual `sync-*` events tagged with `job_id`, and
+/// each status change as `sync-job`.
+#[tauri::command]
+async fn enqueue_sync(app: AppHandle, state: State<'_, SyncState>, request: SyncRequest) -> Result<u64, AppError> {
+  check_request(&app, &request).await?;
+  let queue = state.queue.clone();
+  let id = state.next_job_id();
+  let job = QueuedJob {
+    id,
+    request: SyncRequest { job_id: Some(id), ..request },
+    status: JobStatus::Queued,
+    cancel: Arc::new(AtomicBool::new(false)),
+    error: None,
+    result_count: 0,
+  };
+  {
+    let mut jobs = queue.jobs.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    let _ = app.emit("sync-job", job.info());
+    jobs.push(job);
+  }
+  queue.wake.notify_one();
+  if !queue.worker_started.swap(true, Ordering::SeqCst) {
+    std::thread::spawn(move || run_job_queue(app, queue));
+  }
+  Ok(id)
+}
+
+#[tauri::command]
+fn list_jobs(state: State<'_, SyncState>) -> Result<Vec<JobInfo>, AppError> {
+  let jobs = state.queue.jobs.lock().map_err(|err| AppError::Other(err.to_string()))?;
+  Ok(jobs.iter().map(QueuedJob::info).collect())
+}
+
+/// Cancels job `job_id`, whether run by `start_sync` or queued. Without an
+/// id, cancels every `start_sync` run in flight.
+#[tauri::command]
+fn cancel_sync(app: AppHandle, state: State<'_, SyncState>, job_id: Option<u64>) -> Result<(), AppError> {
+  {
+    let running = state.running.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    match job_id {
+      None => {
+        for token in running.values() {
+          token.store(true, Ordering::SeqCst);
+        }
+        return Ok(());
+      }
+      Some(id) => {
+        if let Some(token) = running.get(&id) {
+          token.store(true, Ordering::SeqCst);
+          return Ok(());
+        }
+      }
+    }
+  }
+  let Some(id) = job_id else {
+    return Ok(());
+  };
+  let status = {
+    let jobs = state.queue.jobs.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    let job = jobs
+      .iter()
+      .find(|job| job.id == id)
+      .ok_or_else(|| AppError::InvalidRequest(format!("No job {id}")))?;
+    job.cancel.store(true, Ordering::SeqCst);
+    job.status
+  };
+  match status {
+    JobStatus::Queued => {
+      state.queue.update(&app, id, |job| job.status = JobStatus::Canceled);
+      Ok(())
+    }
+    JobStatus::Running => Ok(()),
+    _ => Err(AppError::InvalidRequest(format!("Job {id} already finished"))),
+  }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct WatchRequest {
+  folder: String,
+  /// Template for syncing each new file; its video selection is replaced by
+  /// the detected file. Files are only reported when absent.
+  sync: Option<SyncRequest>,
+  /// Seconds a file's size must hold still before it counts as complete.
+  stable_secs: Option<u64>,
+}
+
+const DEFAULT_WATCH_STABLE_SECS: u64 = 5;
+
+/// Pause before re-arming the watcher after the folder became unavailable.
+const WATCH_RETRY_SECS: u64 = 5;
+
+/// The folder watcher, kept apart from `SyncState` so `cancel_sync` leaves
+/// it running.
+#[derive(Default)]
+struct WatchState {
+  active: Mutex<Option<WatchHandle>>,
+}
+
+struct WatchHandle {
+  stop: Arc<AtomicBool>,
+  thread: std::thread::JoinHandle<()>,
+}
+
+impl WatchState {
+  /// Signals the watcher to stop and hands back its thread for joining.
+  fn take(&self) -> Option<std::thread::JoinHandle<()>> {
+    let handle = self.active.lock().ok()?.take()?;
+    handle.stop.store(true, Ordering::SeqCst);
+    Some(handle.thread)
+  }
+}
+
+/// Watches `request.folder` for new video files, emitting
+/// `watch-file-detected` once each stops growing and, when `request.sync` is
+/// set, syncing it. Replaces any watch already running.
+#[tauri::command]
+async fn start_watch(app: AppHandle, state: State<'_, WatchState>, request: WatchRequest) -> Result<(), AppError> {
+  if !Path::new(&request.folder).is_dir() {
+    return Err(AppError::Io(format!("Not a folder: {}", request.folder)));
+  }
+  if let Some(template) = &request.sync {
+    // Checked as the first detected file's job will see it, so a bad
+    // template fails here rather than once per file later.
+    let job = SyncRequest { video_folder: Some(request.folder.clone()), video_folders: None, ..template.clone() };
+    check_request(&app, &job).await?;
+  }
+  if let Some(previous) = state.take() {
+    tauri::async_runtime::spawn_blocking(move || previous.join())
+      .await
+      .map_err(|err| AppError::Other(err.to_string()))?
+      .map_err(|_| AppError::Other("Previous watcher panicked".to_string()))?;
+  }
+
+  let stop = Arc::new(AtomicBool::new(false));
+  let thread = {
+    let stop = stop.clone();
+    std::thread::spawn(move || watch_folder(app, request, stop))
+  };
+  let mut active = state.active.lock().map_err(|err| AppError::Other(err.to_string()))?;
+  *active = Some(WatchHandle { stop, thread });
+  Ok(())
+}
+
+#[tauri::command]
+async fn stop_watch(state: State<'_, WatchState>) -> Result<(), AppError> {
+  if let Some(thread) = state.take() {
+    tauri::async_runtime::spawn_blocking(move || thread.join())
+      .await
+      .map_err(|err| AppError::Other(err.to_string()))?
+      .map_err(|_| AppError::Other("Watcher panicked".to_string()))?;
+  }
+  Ok(())
+}
+
+fn watch_folder(app: AppHandle, request: WatchRequest, stop: Arc<AtomicBool>) {
+  let folder = PathBuf::from(&request.folder);
+  let stable_for = Duration::from_secs(request.stable_secs.unwrap_or(DEFAULT_WATCH_STABLE_SECS));
+
+  // Detected files sync one at a time on their own thread so detection
+  // keeps going during a long sync. Stopping the watch cancels the sync.
+  let (jobs, worker) = match request.sync.clone() {
+    Some(template) => {
+      let (tx, rx) = std::sync::mpsc::channel::<PathBuf>();
+      let (app, folder, stop) = (app.clone(), folder.clone(), stop.clone());
+      let worker = std::thread::spawn(move || {
+        for video in rx {
+          if stop.load(Ordering::SeqCst) {
+            break;
+          }
+          let result = watch_job(&app, &template, &folder, &video)
+            .map_err(AppError::InvalidRequest)
+            .and_then(|job| run_bridge(app.clone(), job, stop.clone()));
+          if let Err(err) = result {
+            emit_log(&app, None, format!("Watch sync failed for {}: {err}", video.display()));
+          }
+        }
+      });
+      (Some(tx), Some(worker))
+    }
+    None => (None, None),
+  };
+
+  // Files already present when the watch starts aren't new.
+  let mut seen: HashSet<PathBuf> = folder_entries(&folder)
+    .into_iter()
+    .map(|(_, path)| PathBuf::from(path))
+    .collect();
+  let mut pending: HashMap<PathBuf, (u64, Instant)> = HashMap::new();
+  let (tx, rx) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+  let mut watcher: Option<notify::RecommendedWatcher> = None;
+  let mut retry_at = Instant::now();
+
+  while !stop.load(Ordering::SeqCst) {
+    if watcher.is_none() && Instant::now() >= retry_at {
+      watcher = arm_watcher(&folder, tx.clone());
+      let available = watcher.is_some();
+      let _ = app.emit(
+        "watch-status",
+        serde_json::json!({ "folder": request.folder, "available": available }),
+      );
+      if available {
+        // Catch files that arrived while the folder was unavailable.
+        for (_, path) in folder_entries(&folder) {
+          let path = PathBuf::from(path);
+          if !seen.contains(&path) && has_extension(&path, &VIDEO_EXTENSIONS) {
+            pending.entry(path).or_insert((u64::MAX, Instant::now()));
+          }
+        }
+      } else {
+        retry_at = Instant::now() + Duration::from_secs(WATCH_RETRY_SECS);
+      }
+    }
+
+    match rx.recv_timeout(Duration::from_millis(500)) {
+      Ok(Ok(event)) => {
+        for path in event.paths {
+          if !seen.contains(&path) && has_extension(&path, &VIDEO_EXTENSIONS) {
+            pending.entry(path).or_insert((u64::MAX, Instant::now()));
+          }
+        }
+      }
+      Ok(Err(err)) => {
+        log::warn!("Watch error on {}: {err}", folder.display());
+        watcher = None;
+      }
+      Err(_) => {}
+    }
+    if watcher.is_some() && !folder.is_dir() {
+      watcher = None;
+    }
+    if watcher.is_none() && retry_at <= Instant::now() {
+      let _ = app.emit(
+        "watch-status",
+        serde_json::json!({ "folder": request.folder, "available": false }),
+      );
+      retry_at = Instant::now() + Duration::from_secs(WATCH_RETRY_SECS);
+    }
+
+    // A file is complete once its size has held still for `stable_for`;
+    // files that vanish (temp files renamed away) are dropped.
+    let mut ready = Vec::new();
+    pending.retain(|path, (size, since)| {
+      let Ok(meta) = fs::metadata(path) else {
+        return false;
+      };
+      if meta.len() != *size {
+        *size = meta.len();
+        *since = Instant::now();
+        return true;
+      }
+      if meta.len() == 0 || since.elapsed() < stable_for {
+        return true;
+      }
+      ready.push((path.clone(), meta.len()));
+      false
+    });
+    for (path, size) in ready {
+      seen.insert(path.clone());
+      let queued = jobs.as_ref().is_some_and(|jobs| jobs.send(path.clone()).is_ok());
+      let _ = app.emit(
+        "watch-file-detected",
+        serde_json::json!({
+          "path": path.to_string_lossy(),
+          "name": file_display_name(&path),
+          "size": size,
+          "queued": queued,
+        }),
+      );
+    }
+  }
+
+  drop(jobs);
+  if let Some(worker) = worker {
+    let _ = worker.join();
+  }
+}
+
+fn arm_watcher(
+  folder: &Path,
+  tx: std::sync::mpsc::Sender<notify::Result<notify::Event>>,
+) -> Option<notify::RecommendedWatcher> {
+  use notify::Watcher;
+  let mut watcher = notify::recommended_watcher(tx).ok()?;
+  watcher.watch(folder, notify::RecursiveMode::NonRecursive).ok()?;
+  Some(watcher)
+}
+
+/// Builds the sync for one detected video from the watch template.
+fn watch_job(app: &AppHandle, template: &SyncRequest, folder: &Path, video: &Path) -> Result<SyncRequest, String> {
+  let name = file_display_name(video);
+  let mut job = SyncRequest {
+    video_folder: Some(folder.to_string_lossy().to_string()),
+    video_folders: None,
+    video_files: Some(vec![video.to_string_lossy().to_string()]),
+    pairs: None,
+    ..template.clone()
+  };
+  if job.mode == "series" {
+    // Series pairing covers the whole folder; keep only the new file's pair.
+    let pairs: Vec<PlannedPair> = plan_pairs(app, &job)?
+      .pairs
+      .into_iter()
+      .filter(|pair| file_display_name(Path::new(&pair.video)) == name)
+      .collect();
+    if pairs.is_empty() {
+      return Err(format!("No matching audio for {name}"));
+    }
+    job.pair_confidence = pairs.iter().map(|pair| pair.confidence).collect();
+    job.pairs = Some(pairs.into_iter().map(|pair| (pair.video, pair.audio)).collect());
+    // Already applied; left in place they would add the other videos back.
+    job.pair_overrides = None;
+  }
+  // `start_watch` checked the template; the output names depend on the
+  // pairs, so each job is checked again as `start_sync` would.
+  let job = resolve_pairs(app, job)?;
+  job.validate()?;
+  Ok(job)
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+struct MediaProbe {
+  has_audio: bool,
+  has_video: bool,
+  duration: Option<f64>,
+  video_codec: Option<String>,
+  audio_codec: Option<String>,
+  width: Option<u32>,
+  height: Option<u32>,
+  /// Average frame rate of the first video stream.
+  frame_rate: Option<f64>,
+  sample_rate: Option<u32>,
+  channels: Option<u32>,
+  tags: MediaTags,
+  audio_streams: Vec<AudioStreamInfo>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+struct AudioStreamInfo {
+  /// ffprobe's stream index within the file, counting every stream type.
+  index: u32,
+  language: Option<String>,
+  codec: Option<String>,
+  channels: Option<u32>,
+}
+
+impl AudioStreamInfo {
+  /// e.g. `#2 (jpn, ac3)`.
+  fn describe(&self) -> String {
+    let details: Vec<&str> = [self.language.as_deref(), self.codec.as_deref()].into_iter().flatten().collect();
+    if details.is_empty() {
+      format!("#{}", self.index)
+    } else {
+      format!("#{} ({})", self.index, details.join(", "))
+    }
+  }
+}
+
+/// Episode metadata read from container or stream tags.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+struct MediaTags {
+  show: Option<String>,
+  season: Option<u32>,
+  episode: Option<u32>,
+  title: Option<String>,
+}
+
+impl MediaTags {
+  /// Reads the first of each field's usual tag names, matching keys
+  /// case-insensitively. Numbers like `3/10` keep their leading part.
+  fn from_tags(tags: &[&serde_json::Map<String, serde_json::Value>]) -> Self {
+    let find = |keys: &[&str]| {
+      keys.iter().find_map(|key| {
+        tags.iter().find_map(|map| {
+          map
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .and_then(|(_, value)| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+        })
+      })
+    };
+    let number = |keys: &[&str]| {
+      find(keys).and_then(|value| {
+        let digits: String = value.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+      })
+    };
+    Self {
+      show: find(&["show", "tvshow", "series"]),
+      season: number(&["season_number", "season", "tvsn"]),
+      episode: number(&["episode_sort", "episode", "episode_number", "tves"]),
+      title: find(&["title"]),
+    }
+  }
+}
+
+#[tauri::command]
+fn probe_media(app: AppHandle, path: String) -> Result<MediaProbe, AppError> {
+  let ffprobe = resolve_ffprobe(&app).map_err(AppError::ProbeFailed)?;
+  probe_cached(&app, &ffprobe, &path, None).map_err(AppError::ProbeFailed)
+}
+
+#[tauri::command]
+fn clear_probe_cache(cache: State<'_, ProbeCache>) -> Result<(), AppError> {
+  cache.entries.lock().map_err(|err| AppError::Other(err.to_string()))?.clear();
+  Ok(())
+}
+
+/// Probes `path`, reusing the cached result while the file's mtime is
+/// unchanged. `low_priority` is the running request's, if any.
+fn probe_cached(app: &AppHandle, ffprobe_path: &Path, path: &str, low_priority: Option<bool>) -> Result<MediaProbe, String> {
+  let cache = app.state::<ProbeCache>();
+  let key = PathBuf::from(path);
+  let modified = fs::metadata(&key).and_then(|meta| meta.modified()).ok();
+  if let (Some(modified), Ok(entries)) = (modified, cache.entries.lock()) {
+    if let Some((seen, probe)) = entries.get(&key) {
+      if *seen == modified {
+        return Ok(probe.clone());
+      }
+    }
+  }
+
+  let probe = probe_file(ffprobe_path, path, low_priority)?;
+  if let (Some(modified), Ok(mut entries)) = (modified, cache.entries.lock()) {
+    entries.insert(key, (modified, probe.clone()));
+  }
+  Ok(probe)
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum FileStatus {
+  Ok,
+  Missing,
+  Unreadable,
+  WrongType,
+  NoAudioStream,
+}
+
+#[derive(Debug, Serialize)]
+struct FileValidation {
+  path: String,
+  status: FileStatus,
+  /// `"video"` or `"audio"` by extension, when recognised.
+  kind: Option<&'static str>,
+  message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+  files: Vec<FileValidation>,
+  /// False when ffprobe was skipped or unavailable and only extensions were checked.
+  probed: bool,
+}
+
+/// Checks paths from outside the pickers (drops, saved sessions) before a
+/// sync: existence, readability, extension and, when `probe` isn't `false`
+/// and ffprobe is available, that the expected stream is present.
+#[tauri::command]
+async fn validate_files(app: AppHandle, paths: Vec<String>, probe: Option<bool>) -> Result<ValidationReport, AppError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let ffprobe_path = if probe.unwrap_or(true) {
+      resolve_ffprobe(&app).ok()
+    } else {
+      None
+    };
+    let files = parallel_map(&paths, PROBE_WORKERS, |path| {
+      validate_file(&app, ffprobe_path.as_deref(), path)
+    });
+    ValidationReport {
+      files,
+      probed: ffprobe_path.is_some(),
+    }
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()))
+}
+
+fn validate_file(app: &AppHandle, ffprobe_path: Option<&Path>, path: &str) -> FileValidation {
+  let file = Path::new(path);
+  let kind = media_kind(file);
+  let verdict = |status, message: Option<String>| FileValidation {
+    path: path.to_string(),
+    status,
+    kind,
+    message,
+  };
+
+  match fs::metadata(file) {
+    Ok(meta) if meta.is_file() => {}
+    Ok(_) => return verdict(FileStatus::WrongType, Some("Not a file".to_string())),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return verdict(FileStatus::Missing, None),
+    Err(err) => return verdict(FileStatus::Unreadable, Some(err.to_string())),
+  }
+  if let Err(err) = fs::File::open(file) {
+    return verdict(FileStatus::Unreadable, Some(err.to_string()));
+  }
+  let Some(kind) = kind else {
+    return verdict(FileStatus::WrongType, Some("Unsupported file type".to_string()));
+  };
+  let Some(ffprobe_path) = ffprobe_path else {
+    return verdict(FileStatus::Ok, None);
+  };
+
+  match probe_cached(app, ffprobe_path, path, None) {
+    Ok(probe) if kind == "video" && !probe.has_video => {
+      verdict(FileStatus::WrongType, Some("No video stream found".to_string()))
+    }
+    Ok(probe) if !probe.has_audio => verdict(FileStatus::NoAudioStream, None),
+    Ok(_) => verdict(FileStatus::Ok, None),
+    Err(err) => verdict(FileStatus::Unreadable, Some(err)),
+  }
+}
+
+/// Probes many files at once on the probe pool, returning results in input
+/// order. A path that can't be probed yields an empty `MediaProbe` instead
+/// of failing the batch.
+#[tauri::command]
+async fn probe_media_batch(app: AppHandle, paths: Vec<String>) -> Result<Vec<MediaProbe>, AppError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let Ok(ffprobe_path) = resolve_ffprobe(&app) else {
+      return vec![MediaProbe::default(); paths.len()];
+    };
+    let total = paths.len();
+    let processed = AtomicUsize::new(0);
+    parallel_map(&paths, PROBE_WORKERS, |path| {
+      let probe = probe_cached(&app, &ffprobe_path, path, None).unwrap_or_default();
+      let done = processed.fetch_add(1, Ordering::SeqCst) + 1;
+      let _ = app.emit(
+        "probe-progress",
+        serde_json::json!({ "processed": done, "total": total, "current": file_display_name(Path::new(path)) }),
+      );
+      probe
+    })
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()))
+}
+
+/// Upper bound on concurrent ffprobe processes when probing a listing.
+const PROBE_WORKERS: usize = 4;
+
+const FFPROBE_NOT_FOUND: &str = "ffprobe not found; configure its path in settings";
+
+#[cfg(windows)]
+const FFMPEG_INSTALL_DIRS: &[&str] = &[
+  r"C:\ffmpeg\bin",
+  r"C:\Program Files\ffmpeg\bin",
+  r"C:\ProgramData\chocolatey\bin",
+];
+
+#[cfg(target_os = "macos")]
+const FFMPEG_INSTALL_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin", "/opt/local/bin"];
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const FFMPEG_INSTALL_DIRS: &[&str] = &["/usr/bin", "/usr/local/bin", "/snap/bin"];
+
+/// Locates an ffmpeg-suite binary (`"ffmpeg"` or `"ffprobe"`): the configured
+/// path first, then the bundled copy, common install locations and `PATH`.
+fn find_ffmpeg_tool(app: &AppHandle, tool: &str) -> Option<PathBuf> {
+  let exe = if cfg!(windows) { format!("{tool}.exe") } else { tool.to_string() };
+  let mut candidates = Vec::new();
+  if let Some(dir) = load_settings_file(app).ffmpeg_path.as_deref().and_then(ffmpeg_dir) {
+    candidates.push(dir.join(&exe));
+  }
+  if let Ok(bundled) = app
+    .path()
+    .resolve(format!("resources/ffmpeg/{exe}"), BaseDirectory::Resource)
+  {
+    candidates.push(bundled);
+  }
+  candidates.extend(FFMPEG_INSTALL_DIRS.iter().map(|dir| Path::new(dir).join(&exe)));
+  if let Some(path) = std::env::var_os("PATH") {
+    candidates.extend(std::env::split_paths(&path).map(|dir| dir.join(&exe)));
+  }
+  candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+/// Folder holding the ffmpeg binaries for a configured path, which may name
+/// either a binary or the folder itself.
+fn ffmpeg_dir(configured: &str) -> Option<PathBuf> {
+  let path = PathBuf::from(configured);
+  if path.is_dir() {
+    Some(path)
+  } else {
+    path.parent().map(Path::to_path_buf)
+  }
+}
+
+fn resolve_ffprobe(app: &AppHandle) -> Result<PathBuf, String> {
+  find_ffmpeg_tool(app, "ffprobe").ok_or_else(|| FFPROBE_NOT_FOUND.to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct FfmpegPaths {
+  /// The path stored in settings, if any.
+  configured: Option<String>,
+  /// Binaries actually resolved from the configured path or discovery.
+  ffmpeg: Option<String>,
+  ffprobe: Option<String>,
+}
+
+#[tauri::command]
+fn get_ffmpeg_path(app: AppHandle) -> FfmpegPaths {
+  let resolved = |tool| find_ffmpeg_tool(&app, tool).map(|path| path.to_string_lossy().to_string());
+  FfmpegPaths {
+    configured: load_settings_file(&app).ffmpeg_path,
+    ffmpeg: resolved("ffmpeg"),
+    ffprobe: resolved("ffprobe"),
+  }
+}
+
+/// Stores a custom ffmpeg location; `None` reverts to automatic discovery.
+#[tauri::command]
+fn set_ffmpeg_path(app: AppHandle, path: Option<String>) -> Result<FfmpegPaths, AppError> {
+  let path = path.filter(|path| !path.trim().is_empty());
+  if let Some(path) = &path {
+    let dir = ffmpeg_dir(path).filter(|dir| dir.is_dir());
+    let found = dir.is_some_and(|dir| {
+      ["ffmpeg", "ffprobe"].iter().any(|tool| {
+        let exe = if cfg!(windows) { format!("{tool}.exe") } else { tool.to_string() };
+        dir.join(exe).is_file()
+      })
+    });
+    if !found {
+      return Err(AppError::InvalidRequest(format!("No ffmpeg or ffprobe found at {path}")));
+    }
+  }
+  update_settings(&app, |settings| settings.ffmpeg_path = path).map_err(AppError::Io)?;
+  Ok(get_ffmpeg_path(app))
+}
+
+/// What `apply_sync` writes.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum ApplyMode {
+  /// The video with the shifted audio muxed in as its only audio track.
+  #[default]
+  Mux,
+  /// Just the shifted audio, re-encoded for the output's extension.
+  Audio,
+}
+
+/// ffmpeg arguments for `apply_sync`. A positive delay starts the audio
+/// later. Muxing shifts timestamps with `-itsoffset` and copies streams;
+/// a standalone track has no video to line up with, so the delay is baked
+/// in with `adelay` or `atrim`.
+fn apply_sync_args(video: &str, audio: &str, delay_ms: f64, output: &str, mode: ApplyMode) -> Vec<String> {
+  let seconds = format!("{:.3}", delay_ms / 1000.0);
+  let mut args: Vec<String> = ["-hide_banner", "-y", "-nostdin"].map(String::from).to_vec();
+  match mode {
+    ApplyMode::Mux => {
+      args.extend(["-i".into(), video.into(), "-itsoffset".into(), seconds, "-i".into(), audio.into()]);
+      args.extend(["-map", "0:v", "-map", "1:a", "-c", "copy"].map(String::from));
+    }
+    ApplyMode::Audio => {
+      let filter = if delay_ms >= 0.0 {
+        format!("adelay=delays={}:all=1", delay_ms.round())
+      } else {
+        format!("atrim=start={:.3},asetpts=PTS-STARTPTS", -delay_ms / 1000.0)
+      };
+      args.extend(["-i".into(), audio.into(), "-vn".into(), "-af".into(), filter]);
+    }
+  }
+  args.push(output.to_string());
+  args
+}
+
+/// Tokens `SyncRequest.filename_template` may use. `{season}` and
+/// `{episode}` are two-digit numbers from the video name, empty when unknown.
+const FILENAME_TOKENS: [&str; 5] = ["video_stem", "audio_stem", "delay_ms", "season", "episode"];
+
+const DEFAULT_FILENAME_TEMPLATE: &str = "{video_stem}.synced";
+
+/// Replaces each `{token}` in `template` with `value(token)`, rejecting
+/// tokens it returns `None` for and stray braces.
+fn render_filename_template(template: &str, value: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+  let mut rendered = String::new();
+  let mut rest = template;
+  while let Some(brace) = rest.find(['{', '}']) {
+    if rest[brace..].starts_with('}') {
+      return Err(format!("Unmatched '}}' in filename template \"{template}\""));
+    }
+    rendered.push_str(&rest[..brace]);
+    let after = &rest[brace + 1..];
+    let close = after
+      .find('}')
+      .ok_or_else(|| format!("Unclosed '{{' in filename template \"{template}\""))?;
+    let token = &after[..close];
+    let known = FILENAME_TOKENS.contains(&token);
+    match value(token).filter(|_| known) {
+      Some(text) => rendered.push_str(&text),
+      None => {
+        return Err(format!(
+          "Unknown token {{{token}}} in filename template; use one of {}",
+          FILENAME_TOKENS.map(|token| format!("{{{token}}}")).join(", ")
+        ))
+      }
+    }
+    rest = &after[close + 1..];
+  }
+  rendered.push_str(rest);
+  Ok(rendered)
+}
+
+/// Output naming settings taken from a `SyncRequest`.
+#[derive(Default)]
+struct OutputTemplate<'a> {
+  output_dir: Option<&'a str>,
+  filename_template: Option<&'a str>,
+  /// Custom match pattern for `{season}`/`{episode}`.
+  custom: Option<&'a Regex>,
+}
+
+impl OutputTemplate<'_> {
+  /// Output file for syncing `audio` onto `video`, keeping the extension of
+  /// the input `mode` writes from.
+  fn path(&self, video: &str, audio: &str, delay_ms: Option<f64>, mode: ApplyMode) -> Result<PathBuf, String> {
+    let video_path = Path::new(video);
+    let audio_path = Path::new(audio);
+    let stem = |path: &Path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let tag = parse_episode(&file_display_name(video_path), self.custom);
+    let name = render_filename_template(self.filename_template.unwrap_or(DEFAULT_FILENAME_TEMPLATE), |token| {
+      Some(match token {
+        "video_stem" => stem(video_path),
+        "audio_stem" => stem(audio_path),
+        "delay_ms" => delay_ms.map(|delay| format!("{}", delay.round())).unwrap_or_default(),
+        "season" => tag.as_ref().and_then(|tag| tag.season).map(|season| format!("{season:02}")).unwrap_or_default(),
+        "episode" => tag.as_ref().map(|tag| format!("{:02}", tag.episodes[0])).unwrap_or_default(),
+        _ => return None,
+      })
+    })?;
+    if name.is_empty() || name.contains(['/', '\\']) {
+      return Err(format!("Filename template produced an unusable name: \"{name}\""));
+    }
+    let source = match mode {
+      ApplyMode::Mux => video_path,
+      ApplyMode::Audio => audio_path,
+    };
+    let file_name = match source.extension() {
+      Some(ext) => format!("{name}.{}", ext.to_string_lossy()),
+      None => name,
+    };
+    let dir = match self.output_dir {
+      Some(dir) => PathBuf::from(dir),
+      None => video_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let output = dir.join(file_name);
+    let target = resolved_path(&output);
+    if let Some(input) = [video_path, audio_path].into_iter().find(|input| resolved_path(input) == target) {
+      return Err(format!("Output {} would overwrite the input {}", output.display(), input.display()));
+    }
+    Ok(output)
+  }
+}
+
+/// Seconds in an ffmpeg `HH:MM:SS.xx` timestamp.
+fn parse_ffmpeg_time(text: &str) -> Option<f64> {
+  let mut parts = text.trim().splitn(3, ':');
+  let hours: f64 = parts.next()?.parse().ok()?;
+  let minutes: f64 = parts.next()?.parse().ok()?;
+  let seconds: f64 = parts.next()?.parse().ok()?;
+  Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// How long `apply_sync` lets ffmpeg go without printing anything before
+/// killing it.
+const APPLY_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// `path` with symlinks and `..` resolved. A file that doesn't exist yet is
+/// resolved through its parent folder.
+fn resolved_path(path: &Path) -> PathBuf {
+  if let Ok(path) = fs::canonicalize(path) {
+    return path;
+  }
+  match (path.parent().and_then(|dir| fs::canonicalize(dir).ok()), path.file_name()) {
+    (Some(dir), Some(name)) => dir.join(name),
+    _ => path.to_path_buf(),
+  }
+}
+
+/// Writes `output_path` with `audio` shifted by `delay_ms`, by muxing it onto
+/// `video` (the default) or as a standalone track. Without `output_path`,
+/// writes `<video stem>.synced` next to the video. Progress is read from
+/// ffmpeg's stderr and emitted as `apply-progress` with the run's `job_id`,
+/// the caller's `job_id` when given so it can be canceled from the start;
+/// `cancel_sync` stops it like a sync, as does ffmpeg going silent for
+/// `APPLY_STALL_TIMEOUT`. A run that doesn't finish cleanly removes its
+/// partial output.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn apply_sync(
+  app: AppHandle,
+  state: State<'_, SyncState>,
+  video: String,
+  audio: String,
+  delay_ms: f64,
+  output_path: Option<String>,
+  mode: Option<ApplyMode>,
+  job_id: Option<u64>,
+) -> Result<String, AppError> {
+  let ffmpeg = find_ffmpeg_tool(&app, "ffmpeg")
+    .ok_or_else(|| AppError::Other("ffmpeg not found; configure its path in settings".to_string()))?;
+  let mode = mode.unwrap_or_default();
+  if !delay_ms.is_finite() {
+    return Err(AppError::InvalidRequest("Delay must be a finite number".to_string()));
+  }
+  if mode == ApplyMode::Mux && !Path::new(&video).is_file() {
+    return Err(AppError::Io(format!("Video not found: {video}")));
+  }
+  if !Path::new(&audio).is_file() {
+    return Err(AppError::Io(format!("Audio not found: {audio}")));
+  }
+  let output_path = match output_path {
+    Some(path) => path,
+    None => OutputTemplate::default()
+      .path(&video, &audio, Some(delay_ms), mode)
+      .map_err(AppError::InvalidRequest)?
+      .to_string_lossy()
+      .to_string(),
+  };
+  if let Some(dir) = Path::new(&output_path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+    fs::create_dir_all(dir).map_err(|err| AppError::Io(format!("Can't create {}: {err}", dir.display())))?;
+  }
+  // ffmpeg runs with `-y`, so this is all that stands between a bad output
+  // path and an overwritten source.
+  let target = resolved_path(Path::new(&output_path));
+  if [&video, &audio].into_iter().any(|input| resolved_path(Path::new(input)) == target) {
+    return Err(AppError::InvalidRequest(format!("Output {output_path} would overwrite one of its inputs")));
+  }
+  // The output is about as large as the input it's written from.
+  let source = match mode {
+    ApplyMode::Mux => &video,
+    ApplyMode::Audio => &audio,
+  };
+  let folder = Path::new(&output_path).parent().map(Path::to_path_buf).unwrap_or_default();
+  let size = fs::metadata(source).map(|meta| meta.len()).unwrap_or(0);
+  check_space(&app, None, HashMap::from([(folder, size)])).map_err(AppError::Io)?;
+
+  let job_id = state.claim_job_id(job_id)?;
+  let cancel = Arc::new(AtomicBool::new(false));
+  let running = state.running.clone();
+  {
+    let mut running = running.lock().map_err(|err| AppError::Other(err.to_string()))?;
+    if running.contains_key(&job_id) {
+      return Err(AppError::InvalidRequest(format!("Job {job_id} is already running")));
+    }
+    running.insert(job_id, cancel.clone());
+  }
+  let result = tauri::async_runtime::spawn_blocking(move || {
+    let args = apply_sync_args(&video, &audio, delay_ms, &output_path, mode);
+    let mut command = tool_command(&ffmpeg, None);
+    command.args(&args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped());
+    ProcessTree::isolate(&mut command);
+    let mut child = command.spawn().map_err(|err| AppError::Io(format!("Failed to start ffmpeg: {err}")))?;
+    let stderr = child.stderr.take().ok_or_else(|| AppError::Io("ffmpeg stderr unavailable".to_string()))?;
+    let tree = Arc::new(ProcessTree::attach(child, Some(job_id)));
+
+    let finished = Arc::new(AtomicBool::new(false));
+    let stalled = Arc::new(AtomicBool::new(false));
+    let last_output = Arc::new(Mutex::new(Instant::now()));
+    let watcher = {
+      let (app, tree, cancel, finished, stalled, last_output) =
+        (app.clone(), tree.clone(), cancel.clone(), finished.clone(), stalled.clone(), last_output.clone());
+      std::thread::spawn(move || {
+        while !finished.load(Ordering::SeqCst) {
+          let quiet = last_output.lock().map(|seen| seen.elapsed() > APPLY_STALL_TIMEOUT).unwrap_or(false);
+          if quiet {
+            stalled.store(true, Ordering::SeqCst);
+          }
+          if quiet || cancel.load(Ordering::SeqCst) {
+            tree.stop(&app);
+            return;
+          }
+          std::thread::sleep(Duration::from_millis(100));
+        }
+      })
+    };
+
+    let file = file_display_name(Path::new(&output_path));
+    let mut duration = None;
+    let mut last_line = String::new();
+    let mut stderr = BufReader::new(stderr);
+    let mut chunk = Vec::new();
+    // Stats lines end in `\r`, so split on both line endings.
+    while stderr.read_until(b'\r', &mut chunk).unwrap_or(0) > 0 {
+      if let Ok(mut seen) = last_output.lock() {
+        *seen = Instant::now();
+      }
+      for line in String::from_utf8_lossy(&chunk).split('\n').map(str::trim).filter(|line| !line.is_empty()) {
+        if duration.is_none() {
+          duration = line
+            .strip_prefix("Duration:")
+            .and_then(|rest| parse_ffmpeg_time(rest.split(',').next().unwrap_or_default()))
+            .filter(|seconds| *seconds > 0.0);
+        }
+        if let (Some(total), Some(time)) = (duration, line.split("time=").nth(1)) {
+          if let Some(done) = parse_ffmpeg_time(time.split_whitespace().next().unwrap_or_default()) {
+            let percent = (done / total * 100.0).clamp(0.0, 100.0) as u8;
+            let progress = serde_json::json!({ "job_id": job_id, "file": file, "percent": percent });
+            let _ = app.emit("apply-progress", progress);
+          }
+        }
+        last_line = line.to_string();
+      }
+      chunk.clear();
+    }
+
+    let status = wait_for_child(&tree.child);
+    finished.store(true, Ordering::SeqCst);
+    let _ = watcher.join();
+    let failure = if stalled.load(Ordering::SeqCst) {
+      Some(AppError::Other(format!("ffmpeg made no progress for {}s", APPLY_STALL_TIMEOUT.as_secs())))
+    } else if cancel.load(Ordering::SeqCst) {
+      Some(AppError::Canceled)
+    } else {
+      match status {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(AppError::Other(format!("ffmpeg failed ({status}): {last_line}"))),
+        Err(err) => Some(AppError::Io(err)),
+      }
+    };
+    if let Some(err) = failure {
+      // Whatever ffmpeg got through is a truncated or broken file.
+      let _ = fs::remove_file(&output_path);
+      return Err(err);
+    }
+    let _ = app.emit("apply-progress", serde_json::json!({ "job_id": job_id, "file": file, "percent": 100 }));
+    Ok(output_path)
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()));
+  if let Ok(mut running) = running.lock() {
+    running.remove(&job_id);
+  }
+  result?
+}
+
+fn probe_file(ffprobe_path: &Path, path: &str, low_priority: Option<bool>) -> Result<MediaProbe, String> {
+  let output = tool_command(ffprobe_path, low_priority)
+    .args([
+      "-v",
+      "error",
+      "-show_entries",
+      "format=duration:format_tags",
+      "-show_streams",
+      "-of",
+      "json",
+      // `-i` keeps a path that starts with `-` from reading as an option.
+      "-i",
+      path,
+    ])
+    .output()
+    .map_err(|err| err.to_string())?;
+
+  if !output.status.success() {
+    return Err("ffprobe failed".to_string());
+  }
+
+  let value: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|err| err.to_string())?;
+  let streams = value.get("streams").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+  let first_of = |kind: &str| {
+    streams
+      .iter()
+      .find(|stream| stream.get("codec_type").and_then(|v| v.as_str()) == Some(kind))
+  };
+  let video = first_of("video");
+  let audio = first_of("audio");
+  let audio_streams = streams
+    .iter()
+    .filter(|stream| stream.get("codec_type").and_then(|v| v.as_str()) == Some("audio"))
+    .filter_map(|stream| {
+      Some(AudioStreamInfo {
+        index: json_u32(stream, "index")?,
+        language: stream.get("tags").and_then(|tags| json_string(tags, "language")),
+        codec: json_string(stream, "codec_name"),
+        channels: json_u32(stream, "channels"),
+      })
+    })
+    .collect();
+
+  let duration = value
+    .get("format")
+    .and_then(|v| v.get("duration"))
+    .and_then(|v| v.as_str())
+    .and_then(|v| v.parse::<f64>().ok());
+  let tag_maps: Vec<&serde_json::Map<String, serde_json::Value>> =
+    [value.get("format"), audio, video]
+      .into_iter()
+      .flatten()
+      .filter_map(|section| section.get("tags").and_then(|tags| tags.as_object()))
+      .collect();
+
+  Ok(MediaProbe {
+    has_audio: audio.is_some(),
+    has_video: video.is_some(),
+    duration,
+    video_codec: video.and_then(|stream| json_string(stream, "codec_name")),
+    audio_codec: audio.and_then(|stream| json_string(stream, "codec_name")),
+    width: video.and_then(|stream| json_u32(stream, "width")),
+    height: video.and_then(|stream| json_u32(stream, "height")),
+    frame_rate: video
+      .and_then(|stream| json_rate(stream, "avg_frame_rate").or_else(|| json_rate(stream, "r_frame_rate"))),
+    sample_rate: audio.and_then(|stream| json_u32(stream, "sample_rate")),
+    channels: audio.and_then(|stream| json_u32(stream, "channels")),
+    tags: MediaTags::from_tags(&tag_maps),
+    audio_streams,
+  })
+}
+
+/// A rate ffprobe writes as a fraction, e.g. `24000/1001`; `0/0` is unknown.
+fn json_rate(value: &serde_json::Value, key: &str) -> Option<f64> {
+  let (num, den) = value.get(key)?.as_str()?.split_once('/')?;
+  let (num, den) = (num.parse::<f64>().ok()?, den.parse::<f64>().ok()?);
+  (num > 0.0 && den > 0.0).then(|| num / den)
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+  value.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Reads an unsigned field that ffprobe may emit as a number or a string
+/// (`sample_rate` is always a string).
+fn json_u32(value: &serde_json::Value, key: &str) -> Option<u32> {
+  let field = value.get(key)?;
+  field
+    .as_u64()
+    .or_else(|| field.as_str().and_then(|v| v.parse().ok()))
+    .and_then(|v| u32::try_from(v).ok())
+}
+
+/// Fills `FileItem.duration` for every item, leaving `None` where ffprobe
+/// is missing or the file can't be read. Emits `probe-progress` as files finish.
+async fn with_durations(app: AppHandle, mut files: Vec<FileItem>) -> Result<Vec<FileItem>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let Ok(ffprobe_path) = resolve_ffprobe(&app) else {
+      return files;
+    };
+    let total = files.len();
+    let processed = AtomicUsize::new(0);
+    let durations = parallel_map(&files, PROBE_WORKERS, |item| {
+      let duration = probe_cached(&app, &ffprobe_path, &item.path, None).ok().and_then(|probe| probe.duration);
+      let done = processed.fetch_add(1, Ordering::SeqCst) + 1;
+      let _ = app.emit(
+        "probe-progress",
+        serde_json::json!({ "processed": done, "total": total, "current": item.name }),
+      );
+      duration
+    });
+    for (item, duration) in files.iter_mut().zip(durations) {
+      item.duration = duration;
+    }
+    files
+  })
+  .await
+  .map_err(|err| err.to_string())
+}
+
+/// Maps `f` over `items` on up to `workers` threads, preserving input order.
+fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+  T: Sync,
+  R: Send,
+  F: Fn(&T) -> R + Sync,
+{
+  let next = AtomicUsize::new(0);
+  let slots: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+  std::thread::scope(|scope| {
+    for _ in 0..workers.clamp(1, items.len().max(1)) {
+      scope.spawn(|| loop {
+        let index = next.fetch_add(1, Ordering::SeqCst);
+        let Some(item) = items.get(index) else {
+          break;
+        };
+        let value = f(item);
+        if let Ok(mut slot) = slots[index].lock() {
+          *slot = Some(value);
+        }
+      });
+    }
+  });
+  slots
+    .into_iter()
+    .filter_map(|slot| slot.into_inner().ok().flatten())
+    .collect()
+}
+
+#[tauri::command]
+fn open_output_folder(path: String) -> Result<(), AppError> {
+  reveal_path(&PathBuf::from(path)).map_err(AppError::Io)
+}
+
+/// Reveals the newest session log, or the empty log folder, in the file manager.
+#[tauri::command]
+fn open_log_folder(app: AppHandle) -> Result<(), AppError> {
+  let dir = session_log_dir(&app).ok_or_else(|| AppError::Io("App log directory unavailable".to_string()))?;
+  fs::create_dir_all(&dir)?;
+  let latest = session_logs(&dir).pop();
+  reveal_path(latest.as_deref().unwrap_or(&dir)).map_err(AppError::Io)
+}
+
+/// Path of the newest session log, if any run has written one.
+#[tauri::command]
+fn get_last_log_path(app: AppHandle) -> Option<String> {
+  let dir = session_log_dir(&app)?;
+  session_logs(&dir).pop().map(|path| path.to_string_lossy().to_string())
+}
+
+/// Shows `path` selected in the platform file manager.
+fn reveal_path(path: &Path) -> Result<(), String> {
+  if !path.exists() {
+    return Err("Path not found".to_string());
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    Command::new("explorer")
+      .arg("/select,")
+      .arg(path)
+      .spawn()
+      .map_err(|err| err.to_string())?;
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    Command::new("open")
+      .arg("-R")
+      .arg(path)
+      .spawn()
+      .map_err(|err| err.to_string())?;
+  }
+
+  #[cfg(all(unix, not(target_os = "macos")))]
+  {
+    // Best effort: file managers that can select the file, then the
+    // containing folder through xdg-open.
+    let selecting = [("nautilus", "--select"), ("dolphin", "--select"), ("nemo", "")];
+    let selected = selecting.iter().any(|(manager, flag)| {
+      let mut command = Command::new(manager);
+      if !flag.is_empty() {
+        command.arg(flag);
+      }
+      command.arg(path).spawn().is_ok()
+    });
+    if !selected {
+      let folder = path.parent().unwrap_or(Path::new("."));
+      Command::new("xdg-open")
+        .arg(folder)
+        .spawn()
+        .map_err(|err| err.to_string())?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Session logs kept under the app log directory; older ones are deleted.
+const MAX_SESSION_LOGS: usize = 20;
+
+/// A session log stops growing past this size.
+const MAX_SESSION_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+fn session_log_dir(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_log_dir().ok().map(|dir| dir.join("sessions"))
+}
+
+/// Session log files in `dir`, oldest first.
+fn session_logs(dir: &Path) -> Vec<PathBuf> {
+  let mut logs: Vec<PathBuf> = fs::read_dir(dir)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| has_extension(path, &["log"]))
+    .collect();
+  // Names carry a fixed-width millisecond timestamp, so they sort by age.
+  logs.sort();
+  logs
+}
+
+/// One run's `sync-log`, `sync-command` and `sync-error` lines, written to
+/// `sessions/sync-<unix millis>-job<id>.log` under the app log directory
+/// (without the `-job<id>` part for runs that have no job id).
+struct SessionLog {
+  file: Mutex<(fs::File, u64)>,
+  /// Events tagged with another job belong to a run in parallel.
+  job_id: Option<u64>,
+}
+
+impl SessionLog {
+  fn start(app: &AppHandle, request: &SyncRequest) -> Result<Self, String> {
+    let dir = session_log_dir(app).ok_or_else(|| "App log directory unavailable".to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    let logs = session_logs(&dir);
+    for old in logs.iter().take((logs.len() + 1).saturating_sub(MAX_SESSION_LOGS)) {
+      let _ = fs::remove_file(old);
+    }
+    let started = unix_millis(SystemTime::now()).unwrap_or_default();
+    let job = request.job_id.map(|id| format!("-job{id}")).unwrap_or_default();
+    let path = dir.join(format!("sync-{started:013}{job}.log"));
+    let file = fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&path)
+      .map_err(|err| format!("{}: {err}", path.display()))?;
+    let log = SessionLog {
+      file: Mutex::new((file, 0)),
+      job_id: request.job_id,
+    };
+    let request = serde_json::to_string_pretty(request).unwrap_or_default();
+    log.write(&format!("AudioSyncMaster {} sync session\nRequest: {request}", env!("CARGO_PKG_VERSION")));
+    Ok(log)
+  }
+
+  fn write(&self, line: &str) {
+    let Ok(mut guard) = self.file.lock() else {
+      return;
+    };
+    let (file, written) = &mut *guard;
+    if *written >= MAX_SESSION_LOG_BYTES {
+      return;
+    }
+    let line = if *written + line.len() as u64 + 1 > MAX_SESSION_LOG_BYTES {
+      *written = MAX_SESSION_LOG_BYTES;
+      "[log size limit reached; later lines dropped]"
+    } else {
+      *written += line.len() as u64 + 1;
+      line
+    };
+    let _ = writeln!(file, "{line}");
+  }
+
+  /// Copies the run's events into the file until the returned ids are
+  /// unlistened. Only events tagged with the run's `job_id` (or untagged,
+  /// for a run without one) are its own.
+  fn listen(self: &Arc<Self>, app: &AppHandle) -> Vec<tauri::EventId> {
+    ["sync-log", "sync-command", "sync-error"]
+      .into_iter()
+      .map(|event| {
+        let log = self.clone();
+        app.listen(event, move |message| {
+          let payload: serde_json::Value = serde_json::from_str(message.payload()).unwrap_or_default();
+          if payload.get("job_id").and_then(|id| id.as_u64()) != log.job_id {
+            return;
+          }
+          let line = payload.get("message").and_then(|message| message.as_str()).filter(|_| event == "sync-log");
+          match line {
+            Some(text) => log.write(text),
+            None => log.write(&format!("[{event}] {payload}")),
+          }
+        })
+      })
+      .collect()
+  }
+}
+
+/// Runs one sync, recording it in a session log (see `SessionLog`).
+fn run_bridge(
+  app: AppHandle,
+  mut request: SyncRequest,
+  cancel: Arc<AtomicBool>,
+) -> Result<Vec<SyncResult>, AppError> {
+  let heartbeat = Arc::new(Heartbeat::default());
+  request.heartbeat = Some(heartbeat.clone());
+  let stall_after = request.stall_warning_secs.unwrap_or(DEFAULT_STALL_WARNING_SECS);
+  if stall_after > 0 {
+    spawn_stall_timer(app.clone(), request.job_id, heartbeat.clone(), Duration::from_secs(stall_after));
+  }
+  let session = match SessionLog::start(&app, &request) {
+    Ok(session) => Some(Arc::new(session)),
+    Err(err) => {
+      log::warn!("Failed to start the session log: {err}");
+      None
+    }
+  };
+  let listeners = session.as_ref().map(|session| session.listen(&app)).unwrap_or_default();
+  let outcome = run_sync(app.clone(), request, cancel);
+  heartbeat.done.store(true, Ordering::SeqCst);
+  for id in listeners {
+    app.unlisten(id);
+  }
+  if let Some(session) = session {
+    match &outcome {
+      Ok(results) => session.write(&format!("Finished with {} results: {}", results.len(), summarize(results))),
+      Err(err) => session.write(&format!("Failed: {err}")),
+    }
+  }
+  outcome
+}
+
+/// Tracks bridge output for `sync-stalled`. Unarmed until a bridge process
+/// starts, so the checks before it never count as a stall.
+#[derive(Debug, Default)]
+struct Heartbeat {
+  last: Mutex<Option<Instant>>,
+  done: AtomicBool,
+}
+
+impl Heartbeat {
+  fn beat(&self) {
+    if let Ok(mut last) = self.last.lock() {
+      *last = Some(Instant::now());
+    }
+  }
+
+  fn silence(&self) -> Option<Duration> {
+    self.last.lock().ok()?.map(|last| last.elapsed())
+  }
+}
+
+/// Emits `sync-stalled` (`stalled: true` and the seconds since the bridge
+/// last wrote anything) every few seconds while it's been silent for
+/// `stall_after`, and once with `stalled: false` when it speaks again.
+fn spawn_stall_timer(app: AppHandle, job_id: Option<u64>, heartbeat: Arc<Heartbeat>, stall_after: Duration) {
+  const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+  const REPEAT_EVERY: Duration = Duration::from_secs(5);
+  std::thread::spawn(move || {
+    let mut last_warning: Option<Instant> = None;
+    while !heartbeat.done.load(Ordering::SeqCst) {
+      std::thread::sleep(CHECK_INTERVAL);
+      let silence = heartbeat.silence().unwrap_or_default();
+      if silence >= stall_after {
+        if last_warning.map_or(true, |warned| warned.elapsed() >= REPEAT_EVERY) {
+          let payload = serde_json::json!({ "stalled": true, "seconds": silence.as_secs() });
+          let _ = app.emit("sync-stalled", tag_job(job_id, payload));
+          last_warning = Some(Instant::now());
+        }
+      } else if last_warning.take().is_some() {
+        let _ = app.emit("sync-stalled", tag_job(job_id, serde_json::json!({ "stalled": false, "seconds": 0 })));
+      }
+    }
+  });
+}
+
+/// Emits `sync-canceled` with the results finished before the cancel.
+fn emit_sync_canceled(app: &AppHandle, request: &SyncRequest, results: &[SyncResult]) {
+  let _ = app.emit("sync-canceled", request.tagged(serde_json::json!({ "results": results })));
+}
+
+/// Emits `sync-done` with the results and their `summarize` counts.
+fn emit_sync_done(app: &AppHandle, request: &SyncRequest, results: &[SyncResult]) {
+  let _ = app.emit("sync-done", request.tagged(serde_json::json!({ "results": results, "summary": summarize(results) })));
+}
+
+/// Counts of `results` by outcome, e.g. `8 ok, 1 low confidence, 2 errors`.
+/// Low-confidence rows count on their own, never as errors.
+fn summarize(results: &[SyncResult]) -> String {
+  let mut counts: Vec<(&str, usize)> = Vec::new();
+  for result in results {
+    let label = match result.status {
+      Some(ResultStatus::Skipped) => "skipped",
+      Some(ResultStatus::Unmatched) => "unmatched",
+      Some(ResultStatus::Warning) => "drifting",
+      Some(ResultStatus::Planned) => "planned",
+      Some(ResultStatus::LowConfidence) => "low confidence",
+      Some(ResultStatus::TimedOut) => "timed out",
+      None if result.error.is_some() => "errors",
+      None => "ok",
+    };
+    match counts.iter_mut().find(|(name, _)| *name == label) {
+      Some((_, count)) => *count += 1,
+      None => counts.push((label, 1)),
+    }
+  }
+  counts.iter().map(|(label, count)| format!("{count} {label}")).collect::<Vec<_>>().join(", ")
+}
+
+fn run_sync(
+  app: AppHandle,
+  request: SyncRequest,
+  cancel: Arc<AtomicBool>,
+) -> Result<Vec<SyncResult>, AppError> {
+  let derived = request.pairs.is_none();
+  let mut request = resolve_pairs(&app, request).map_err(AppError::InvalidRequest)?;
+  if request.normalize.is_none() {
+    request.normalize = Some(load_settings_file(&app).session.normalize);
+  }
+  // Rows the bridge won't produce: unmatched files, then skipped pairs.
+  let mut prefilled = if derived && request.mode == "series" {
+    unmatched_rows(&app, &request).map_err(AppError::InvalidRequest)?
+  } else {
+    Vec::new()
+  };
+  if request.skip_existing && !request.force {
+    prefilled.extend(skip_existing_pairs(&app, &mut request));
+  }
+  resolve_stream_languages(&app, &mut request);
+  check_streams(&app, &mut request);
+  prefilled.extend(check_selected_streams(&app, &mut request));
+  resolve_downmix(&app, &mut request);
+  warn_unused_file_options(&app, &request);
+  if request.dry_run {
+    return run_dry(&app, &request, &cancel, prefilled);
+  }
+  check_free_space(&app, &request)?;
+  let max_workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+  let concurrency = request.concurrency.unwrap_or(1).clamp(1, max_workers);
+  let retrying = request.retry_escalation.as_ref().is_some_and(|steps| !steps.is_empty());
+  let respawning = request.file_timeout_secs.is_some();
+  let results = if concurrency > 1 {
+    run_sharded(&app, &request, cancel, concurrency, prefilled)?
+  } else if prefilled.is_empty() && !retrying && !respawning {
+    run_bridge_process(&app, &request, cancel, None)?
+  } else {
+    // Run as a batch so the final `sync-done` can carry the prefilled rows,
+    // retries and the results of respawned bridges too.
+    let batch = BatchProgress::new(request.pairs.as_ref().map_or(0, Vec::len));
+    let mut results = prefilled;
+    if batch.total > 0 {
+      match run_respawning(&app, &request, &cancel, &batch) {
+        Ok(synced) => results.extend(synced),
+        Err(AppError::Aborted { file, error, results: synced }) => {
+          results.extend(synced);
+          return Err(AppError::Aborted { file, error, results });
+        }
+        Err(err) => return Err(err),
+      }
+    }
+    let results = retry_failed(&app, &request, &cancel, results);
+    if cancel.load(Ordering::SeqCst) {
+      emit_sync_canceled(&app, &request, &results);
+    } else {
+      emit_sync_done(&app, &request, &results);
+    }
+    results
+  };
+  if let Err(err) = cache_results(&app, &request, &results) {
+    log::warn!("Failed to cache sync results: {err}");
+  }
+  Ok(results)
+}
+
+/// Runs the bridge, starting a new one for the files left whenever a file
+/// times out, so one hanging file doesn't cost the rest of the batch.
+fn run_respawning(
+  app: &AppHandle,
+  request: &SyncRequest,
+  cancel: &Arc<AtomicBool>,
+  batch: &BatchProgress,
+) -> Result<Vec<SyncResult>, AppError> {
+  let mut results = Vec::new();
+  let mut pending = request.clone();
+  loop {
+    let synced = match run_bridge_process(app, &pending, cancel.clone(), Some(batch)) {
+      Ok(synced) => synced,
+      Err(AppError::Aborted { file, error, results: synced }) => {
+        results.extend(synced);
+        return Err(AppError::Aborted { file, error, results });
+      }
+      Err(err) if results.is_empty() => return Err(err),
+      // Keep what the earlier bridges synced.
+      Err(err) => {
+        emit_sync_error(app, request.job_id, "respawn", err.to_string(), None);
+        break;
+      }
+    };
+    let timed_out = synced.iter().any(|result| result.status == Some(ResultStatus::TimedOut));
+    results.extend(synced);
+    if !timed_out || cancel.load(Ordering::SeqCst) {
+      break;
+    }
+    let Some(rest) = pending.remaining_after(&results) else {
+      break;
+    };
+    let count = rest.pairs.as_ref().map_or(0, Vec::len);
+    // Results that can't be tied to their pairs would restart it forever.
+    if count >= pending.pairs.as_ref().map_or(0, Vec::len) {
+      emit_sync_error(app, request.job_id, "respawn", format!("No progress on the {count} remaining files; not restarting the bridge."), None);
+      break;
+    }
+    emit_log(app, request.job_id, format!("Restarting the bridge for the {count} remaining files."));
+    pending = rest;
+  }
+  Ok(results)
+}
+
+/// Emits `sync-warning` for `per_file_options` entries naming no video in
+/// the run, which usually means a typo or a stale path.
+fn warn_unused_file_options(app: &AppHandle, request: &SyncRequest) {
+  let Some(options) = &request.per_file_options else {
+    return;
+  };
+  let videos: HashSet<&String> = request.pairs.iter().flatten().map(|(video, _)| video).collect();
+  for path in options.keys().filter(|path| !videos.contains(path)) {
+    let _ = app.emit(
+      "sync-warning",
+      request.tagged(serde_json::json!({ "file": path, "message": format!("Per-file options for {path} match no selected video") })),
+    );
+  }
+}
+
+/// Successful results from earlier runs, so pairs `skip_existing` skips keep
+/// their measured delays, and output names using `{delay_ms}` can be worked
+/// out. An entry covers a pair with the same video and audio paths and
+/// segment duration whose files still have the modification times recorded
+/// with it. Entries whose inputs changed or were deleted are pruned when the
+/// cache is next read.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ResultCache {
+  entries: Vec<CachedResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedResult {
+  video: String,
+  audio: String,
+  video_modified: u64,
+  audio_modified: u64,
+  segment_duration: f64,
+  result: SyncResult,
+}
+
+/// Serializes read-modify-write cycles on the result cache file.
+static RESULT_CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+fn result_cache_path(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_cache_dir().ok().map(|dir| dir.join("sync-results.json"))
+}
+
+fn load_result_cache(app: &AppHandle) -> ResultCache {
+  result_cache_path(app)
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|raw| serde_json::from_str(&raw).ok())
+    .unwrap_or_default()
+}
+
+fn modified_millis(path: impl AsRef<Path>) -> Option<u64> {
+  fs::metadata(path).and_then(|meta| meta.modified()).ok().and_then(unix_millis)
+}
+
+fn save_result_cache(path: &Path, cache: &ResultCache) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+  }
+  let json = serde_json::to_string(cache).map_err(|err| err.to_string())?;
+  fs::write(path, json.as_bytes()).map_err(|err| err.to_string())
+}
+
+impl CachedResult {
+  fn covers(&self, video: &str, audio: &str, segment_duration: f64) -> bool {
+    self.video == video
+      && self.audio == audio
+      && self.segment_duration == segment_duration
+      && modified_millis(video) == Some(self.video_modified)
+      && modified_millis(audio) == Some(self.audio_modified)
+  }
+
+  /// An input was deleted or modified since the result was recorded. Inputs
+  /// that can't be read for other reasons, like an offline share, keep the
+  /// entry.
+  fn is_stale(&self) -> bool {
+    [(&self.video, self.video_modified), (&self.audio, self.audio_modified)]
+      .into_iter()
+      .any(|(path, recorded)| match fs::metadata(path) {
+        Ok(meta) => meta.modified().ok().and_then(unix_millis).is_some_and(|modified| modified != recorded),
+        Err(err) => err.kind() == std::io::ErrorKind::NotFound,
+      })
+  }
+}
+
+/// Drops stale entries from the result cache file, returning what is left.
+fn prune_result_cache(app: &AppHandle) -> ResultCache {
+  let _guard = RESULT_CACHE_LOCK.lock();
+  let mut cache = load_result_cache(app);
+  let count = cache.entries.len();
+  cache.entries.retain(|entry| !entry.is_stale());
+  if cache.entries.len() < count {
+    if let Some(path) = result_cache_path(app) {
+      let _ = save_result_cache(&path, &cache);
+    }
+  }
+  cache
+}
+
+/// True when `output` exists and is newer than both `video` and `audio`.
+fn output_is_current(output: &Path, video: &str, audio: &str) -> bool {
+  match (modified_millis(output), modified_millis(video), modified_millis(audio)) {
+    (Some(output), Some(video), Some(audio)) => output > video && output > audio,
+    _ => false,
+  }
+}
+
+/// Drops pairs whose output is already current (see `output_is_current`),
+/// emitting `sync-file-skip` and returning them as skipped rows. A cached
+/// result for the pair fills in its delays, and names the output when the
+/// filename template uses `{delay_ms}`; without one such pairs are synced.
+fn skip_existing_pairs(app: &AppHandle, request: &mut SyncRequest) -> Vec<SyncResult> {
+  let cache = prune_result_cache(app);
+  let names_delay = request
+    .filename_template
+    .as_deref()
+    .unwrap_or(DEFAULT_FILENAME_TEMPLATE)
+    .contains("{delay_ms}");
+  let name = |path: &str| file_display_name(Path::new(path));
+  let mut synced: HashMap<(String, String), SyncResult> = HashMap::new();
+  for (video, audio) in request.pairs.iter().flatten() {
+    let segment_duration = request.segment_for(video);
+    let cached = cache
+      .entries
+      .iter()
+      .find(|entry| entry.covers(video, audio, segment_duration))
+      .map(|entry| &entry.result);
+    let delay_ms = cached.and_then(|result| result.startDelay);
+    if names_delay && delay_ms.is_none() {
+      continue;
+    }
+    let Some(output) = request
+      .output_file(video, audio, delay_ms)
+      .filter(|output| output_is_current(output, video, audio))
+    else {
+      continue;
+    };
+    let row = match cached {
+      Some(result) => result.clone(),
+      None => SyncResult {
+        video_path: Some(video.clone()),
+        audio_path: Some(audio.clone()),
+        ..SyncResult::unmatched(name(video), name(audio), request.source_label(audio))
+      },
+    };
+    let result = SyncResult {
+      status: Some(ResultStatus::Skipped),
+      elapsedMs: None,
+      output_path: Some(output.to_string_lossy().to_string()),
+      ..row
+    };
+    synced.insert((video.clone(), audio.clone()), result);
+  }
+  if synced.is_empty() {
+    return Vec::new();
+  }
+
+  let mut skipped = Vec::new();
+  let job_id = request.job_id;
+  retain_pairs(request, |video, audio| {
+    let Some(result) = synced.remove(&(video.to_string(), audio.to_string())) else {
+      return true;
+    };
+    let _ = app.emit(
+      "sync-file-skip",
+      tag_job(job_id, serde_json::json!({ "file": result.videoFile, "reason": "already synced", "result": result })),
+    );
+    let _ = app.emit("sync-result", tag_job(job_id, &result));
+    skipped.push(result);
+    false
+  });
+  skipped
+}
+
+/// Keeps the request's pairs for which `keep` holds, along with their
+/// `pair_confidence` entries.
+fn retain_pairs(request: &mut SyncRequest, mut keep: impl FnMut(&str, &str) -> bool) {
+  let Some(pairs) = request.pairs.take() else {
+    return;
+  };
+  let confidence = request.pair_confidence.take();
+  let mut kept = Vec::new();
+  let mut kept_confidence = Vec::new();
+  for (index, (video, audio)) in pairs.into_iter().enumerate() {
+    if keep(&video, &audio) {
+      kept_confidence.extend(confidence.as_ref().and_then(|confidence| confidence.get(index)));
+      kept.push((video, audio));
+    }
+  }
+  request.pair_confidence = confidence.map(|_| kept_confidence);
+  request.pairs = Some(kept);
+}
+
+/// Probes every input and emits `sync-warning` for videos without a video
+/// or audio stream and audio inputs without an audio stream. With `strict`,
+/// the affected pairs are skipped (`sync-file-skip`) instead of synced.
+fn check_streams(app: &AppHandle, request: &mut SyncRequest) {
+  let Ok(ffprobe) = resolve_ffprobe(app) else {
+    emit_log(app, request.job_id, "ffprobe not found; skipping the stream check.");
+    return;
+  };
+  let mut inputs: Vec<String> = request
+    .pairs
+    .iter()
+    .flatten()
+    .flat_map(|(video, audio)| [video.clone(), audio.clone()])
+    .collect();
+  inputs.sort();
+  inputs.dedup();
+  let probes: HashMap<String, MediaProbe> = inputs
+    .iter()
+    .cloned()
+    .zip(parallel_map(&inputs, PROBE_WORKERS, |path| probe_cached(app, &ffprobe, path, request.low_priority)))
+    .filter_map(|(path, probe)| probe.ok().map(|probe| (path, probe)))
+    .collect();
+
+  let problem = |path: &str, is_video: bool| {
+    let probe = probes.get(path)?;
+    let message = match (is_video, probe.has_video, probe.has_audio) {
+      (true, false, _) => "has no video stream",
+      (true, true, false) => "has no audio stream to sync against",
+      (false, _, false) => "has no audio stream",
+      _ => return None,
+    };
+    Some(format!("{} {message}", file_display_name(Path::new(path))))
+  };
+  let mut warned = HashSet::new();
+  let strict = request.strict;
+  let job_id = request.job_id;
+  retain_pairs(request, |video, audio| {
+    let problems: Vec<(&str, String)> = [(video, true), (audio, false)]
+      .into_iter()
+      .filter_map(|(path, is_video)| problem(path, is_video).map(|message| (path, message)))
+      .collect();
+    for (path, message) in &problems {
+      if warned.insert(path.to_string()) {
+        let _ = app.emit("sync-warning", tag_job(job_id, serde_json::json!({ "file": path, "message": message })));
+      }
+    }
+    if strict && !problems.is_empty() {
+      let _ = app.emit(
+        "sync-file-skip",
+        tag_job(job_id, serde_json::json!({ "file": file_display_name(Path::new(video)), "reason": problems[0].1 })),
+      );
+      return false;
+    }
+    true
+  });
+}
+
+/// Picks the audio stream of each input whose language is asked for (see
+/// `SyncRequest.video_language`) and has no stream index set, recording the
+/// index and the stream's tag in `per_file_options`. Falls back to the first
+/// audio stream with a `sync-warning` when no stream carries the language;
+/// of several that do, the first is used and logged.
+fn resolve_stream_languages(app: &AppHandle, request: &mut SyncRequest) {
+  let pairs = request.pairs.clone().unwrap_or_default();
+  // (video, input to probe, language, whether the input is the video).
+  let mut wanted = Vec::new();
+  for (video, audio) in &pairs {
+    let options = request.per_file_options.as_ref().and_then(|options| options.get(video));
+    let video_language = options.and_then(|options| options.video_language.clone()).or(request.video_language.clone());
+    let audio_language = options.and_then(|options| options.audio_language.clone()).or(request.audio_language.clone());
+    if let (Some(language), None) = (video_language, request.video_stream_for(video)) {
+      wanted.push((video.clone(), video.clone(), language, true));
+    }
+    if let (Some(language), None) = (audio_language, request.audio_stream_for(video)) {
+      wanted.push((video.clone(), audio.clone(), language, false));
+    }
+  }
+  if wanted.is_empty() {
+    return;
+  }
+  let Ok(ffprobe) = resolve_ffprobe(app) else {
+    emit_log(app, request.job_id, "ffprobe not found; can't pick audio streams by language.");
+    return;
+  };
+  for (video, input, language, is_video) in wanted {
+    let name = file_display_name(Path::new(&input));
+    let streams = match probe_cached(app, &ffprobe, &input, request.low_priority) {
+      Ok(probe) => probe.audio_streams,
+      Err(err) => {
+        log::warn!("Couldn't probe {input} for its audio languages: {err}");
+        continue;
+      }
+    };
+    let matching: Vec<&AudioStreamInfo> = streams
+      .iter()
+      .filter(|stream| stream.language.as_deref().is_some_and(|tag| tag.eq_ignore_ascii_case(&language)))
+      .collect();
+    let picked = match matching.as_slice() {
+      [] => {
+        let Some(first) = streams.first() else {
+          continue;
+        };
+        let message = format!("{name} has no {language} audio stream; using {}", first.describe());
+        let _ = app.emit("sync-warning", request.tagged(serde_json::json!({ "file": input, "message": message })));
+        first
+      }
+      [only] => only,
+      [first, ..] => {
+        emit_log(app, request.job_id, format!("{name} has {} {language} audio streams; using {}", matching.len(), first.describe()));
+        first
+      }
+    };
+    let options = request.per_file_options.get_or_insert_with(HashMap::new).entry(video).or_default();
+    if is_video {
+      options.video_audio_stream = Some(picked.index);
+      options.video_language = picked.language.clone();
+    } else {
+      options.audio_stream = Some(picked.index);
+      options.audio_language = picked.language.clone();
+    }
+  }
+}
+
+/// Drops pairs whose video or audio file lacks the audio stream picked for
+/// it, returning an error row for each that names the streams it does have.
+fn check_selected_streams(app: &AppHandle, request: &mut SyncRequest) -> Vec<SyncResult> {
+  let picks: HashMap<String, (Option<u32>, Option<u32>)> = request
+    .pairs
+    .iter()
+    .flatten()
+    .map(|(video, _)| (video.clone(), (request.video_stream_for(video), request.audio_stream_for(video))))
+    .filter(|(_, picks)| picks.0.is_some() || picks.1.is_some())
+    .collect();
+  if picks.is_empty() {
+    return Vec::new();
+  }
+  let Ok(ffprobe) = resolve_ffprobe(app) else {
+    emit_log(app, request.job_id, "ffprobe not found; can't check the selected audio streams.");
+    return Vec::new();
+  };
+  // The problem with `path`'s stream `index`, if it lacks one.
+  let low_priority = request.low_priority;
+  let missing = |path: &str, index: Option<u32>| {
+    let index = index?;
+    let probe = probe_cached(app, &ffprobe, path, low_priority).ok()?;
+    if probe.audio_streams.iter().any(|stream| stream.index == index) {
+      return None;
+    }
+    let available: Vec<String> = probe.audio_streams.iter().map(AudioStreamInfo::describe).collect();
+    let available = if available.is_empty() { "none".to_string() } else { available.join(", ") };
+    Some(format!(
+      "{} has no audio stream #{index}; audio streams: {available}",
+      file_display_name(Path::new(path))
+    ))
+  };
+  let job_id = request.job_id;
+  let mut failed = Vec::new();
+  retain_pairs(request, |video, audio| {
+    let Some(&(video_stream, audio_stream)) = picks.get(video) else {
+      return true;
+    };
+    let Some(error) = missing(video, video_stream).or_else(|| missing(audio, audio_stream)) else {
+      return true;
+    };
+    let result = SyncResult::failed(file_display_name(Path::new(video)), file_display_name(Path::new(audio)), error);
+    let _ = app.emit("sync-result", tag_job(job_id, &result));
+    failed.push(result);
+    false
+  });
+  failed
+}
+
+/// Channels of audio stream `index` in `probe`, or of its first audio stream.
+fn channels_of(probe: &MediaProbe, index: Option<u32>) -> Option<u32> {
+  match index {
+    Some(index) => probe.audio_streams.iter().find(|stream| stream.index == index)?.channels,
+    None => probe.audio_streams.first()?.channels,
+  }
+}
+
+/// Compares the channel counts of each pair's selected streams. Without a
+/// downmix, differing layouts get a `sync-warning`; with one, the mix is
+/// recorded in `per_file_options`, falling back to "mono" for pairs that lack
+/// the channels it needs (a center for "center_only", two for "stereo").
+fn resolve_downmix(app: &AppHandle, request: &mut SyncRequest) {
+  let Ok(ffprobe) = resolve_ffprobe(app) else {
+    emit_log(app, request.job_id, "ffprobe not found; skipping the channel layout check.");
+    return;
+  };
+  for (video, audio) in request.pairs.clone().unwrap_or_default() {
+    let channels = |path: &str, index: Option<u32>| {
+      probe_cached(app, &ffprobe, path, request.low_priority).ok().and_then(|probe| channels_of(&probe, index))
+    };
+    let (Some(video_channels), Some(audio_channels)) = (
+      channels(&video, request.video_stream_for(&video)),
+      channels(&audio, request.audio_stream_for(&video)),
+    ) else {
+      continue;
+    };
+    let name = file_display_name(Path::new(&video));
+    let Some(wanted) = request.downmix_for(&video) else {
+      if video_channels != audio_channels {
+        let message = format!(
+          "{name} has {video_channels} audio channels but {} has {audio_channels}; set a downmix to compare like with like",
+          file_display_name(Path::new(&audio))
+        );
+        let _ = app.emit("sync-warning", request.tagged(serde_json::json!({ "file": video, "message": message })));
+      }
+      continue;
+    };
+    let needed = match wanted.as_str() {
+      "center_only" => 3,
+      "stereo" => 2,
+      _ => 1,
+    };
+    let effective = if video_channels.min(audio_channels) < needed {
+      emit_log(app, request.job_id, format!("{name}: not enough channels for the {wanted} downmix; using mono"));
+      "mono".to_string()
+    } else {
+      wanted
+    };
+    request.per_file_options.get_or_insert_with(HashMap::new).entry(video).or_default().downmix = Some(effective);
+  }
+}
+
+/// Free space below this share over the outputs' estimated size gets a
+/// `sync-warning`.
+const FREE_SPACE_MARGIN: f64 = 0.1;
+
+/// Compares the size of the videos each output folder will receive (their
+/// own folder unless `output_dir` is set) against the free space there. Fails
+/// the run when it clearly can't fit and emits `sync-warning` when it's tight.
+fn check_free_space(app: &AppHandle, request: &SyncRequest) -> Result<(), AppError> {
+  let mut needed: HashMap<PathBuf, u64> = HashMap::new();
+  for (video, _) in request.pairs.iter().flatten() {
+    let folder = match &request.output_dir {
+      Some(dir) => PathBuf::from(dir),
+      None => Path::new(video).parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let size = fs::metadata(video).map(|meta| meta.len()).unwrap_or(0);
+    *needed.entry(folder).or_default() += size;
+  }
+  check_space(app, request.job_id, needed).map_err(AppError::Io)
+}
+
+/// Fails with a message when a folder in `needed` has less free space than
+/// the bytes it's due to receive, and emits `sync-warning` when it's tight.
+fn check_space(app: &AppHandle, job_id: Option<u64>, needed: HashMap<PathBuf, u64>) -> Result<(), String> {
+  for (folder, bytes) in needed {
+    // `output_dir` may not exist yet; its nearest existing ancestor is on the
+    // same volume.
+    let Some(existing) = folder.ancestors().find(|dir| dir.is_dir()) else {
+      continue;
+    };
+    let Some(free) = free_space(existing) else {
+      log::warn!("Couldn't query free space for {}", existing.display());
+      continue;
+    };
+    let gib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    if bytes > free {
+      let message = format!(
+        "Not enough free space in {}: outputs need about {:.1} GiB, {:.1} GiB available",
+        folder.display(),
+        gib(bytes),
+        gib(free)
+      );
+      emit_sync_error(app, job_id, "disk", message.clone(), None);
+      return Err(message);
+    }
+    if bytes as f64 * (1.0 + FREE_SPACE_MARGIN) > free as f64 {
+      let message = format!(
+        "Outputs need about {:.1} GiB of the {:.1} GiB free in {}",
+        gib(bytes),
+        gib(free),
+        folder.display()
+      );
+      let _ = app.emit("sync-warning", tag_job(job_id, serde_json::json!({ "file": folder, "message": message })));
+    }
+  }
+  Ok(())
+}
+
+/// Bytes available to the current user on the volume holding `path`.
+#[cfg(windows)]
+fn free_space(path: &Path) -> Option<u64> {
+  use std::os::windows::ffi::OsStrExt;
+  use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+  let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+  let mut available = 0u64;
+  // SAFETY: `wide` is NUL-terminated and outlives the call; the optional
+  // totals are passed as null.
+  let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+  (ok != 0).then_some(available)
+}
+
+/// Bytes available to the current user on the volume holding `path`.
+#[cfg(unix)]
+fn free_space(path: &Path) -> Option<u64> {
+  use std::os::unix::ffi::OsStrExt;
+  let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+  let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+  // SAFETY: `path` is NUL-terminated and `stats` is only read after
+  // `statvfs` reports having filled it in.
+  let stats = unsafe {
+    if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+      return None;
+    }
+    stats.assume_init()
+  };
+  Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+/// Records the error-free results of a run against their input paths.
+fn cache_results(app: &AppHandle, request: &SyncRequest, results: &[SyncResult]) -> Result<(), String> {
+  let path = result_cache_path(app).ok_or_else(|| "App cache directory unavailable".to_string())?;
+  let pairs = request.pairs.as_deref().unwrap_or_default();
+  let fresh: Vec<CachedResult> = results
+    .iter()
+    .filter(|result| matches!(result.status, None | Some(ResultStatus::Warning)))
+    .filter(|result| result.error.is_none() && result.startDelay.is_some())
+    .filter_map(|result| {
+      let (video, audio) = pairs.iter().find(|(video, audio)| {
+        file_display_name(Path::new(video)) == result.videoFile && file_display_name(Path::new(audio)) == result.audioFile
+      })?;
+      Some(CachedResult {
+        video: video.clone(),
+        audio: audio.clone(),
+        video_modified: modified_millis(video)?,
+        audio_modified: modified_millis(audio)?,
+        segment_duration: request.segment_for(video),
+        result: result.clone(),
+      })
+    })
+    .collect();
+  if fresh.is_empty() {
+    return Ok(());
+  }
+
+  let _guard = RESULT_CACHE_LOCK.lock().map_err(|err| err.to_string())?;
+  let mut cache = load_result_cache(app);
+  cache
+    .entries
+    .retain(|entry| !fresh.iter().any(|new| new.video == entry.video && new.audio == entry.audio));
+  cache.entries.extend(fresh);
+  save_result_cache(&path, &cache)
+}
+
+enum BoundedLine {
+  Line(String),
+  /// A line over the limit, drained without being buffered; holds its length.
+  TooLong(usize),
+}
+
+/// Reads one newline-terminated line, buffering at most `max` bytes. Returns
+/// `None` at end of stream.
+fn read_bounded_line(reader: &mut impl BufRead, max: usize) -> std::io::Result<Option<BoundedLine>> {
+  let mut line = Vec::new();
+  let mut length = 0;
+  let mut terminated = false;
+  while !terminated {
+    let available = match reader.fill_buf() {
+      Ok(available) => available,
+      Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+      Err(err) => return Err(err),
+    };
+    if available.is_empty() {
+      if length == 0 {
+        return Ok(None);
+      }
+      break;
+    }
+    let (chunk, found) = match available.iter().position(|&byte| byte == b'\n') {
+      Some(end) => (&available[..end], true),
+      None => (available, false),
+    };
+    length += chunk.len();
+    if length <= max {
+      line.extend_from_slice(chunk);
+    } else if !line.is_empty() {
+      line = Vec::new();
+    }
+    let used = chunk.len() + usize::from(found);
+    reader.consume(used);
+    terminated = found;
+  }
+
+  if length > max {
+    Ok(Some(BoundedLine::TooLong(length)))
+  } else {
+    Ok(Some(BoundedLine::Line(String::from_utf8_lossy(&line).into_owned())))
+  }
+}
+
+/// Emits `sync-command` with everything needed to rerun the bridge by hand:
+/// program, arguments, working directory and the request line sent on stdin.
+fn emit_sync_command(app: &AppHandle, job_id: Option<u64>, command: &Command, payload: &str) {
+  let cwd = command
+    .get_current_dir()
+    .map(Path::to_path_buf)
+    .or_else(|| std::env::current_dir().ok());
+  let _ = app.emit(
+    "sync-command",
+    tag_job(job_id, serde_json::json!({
+      "program": command.get_program().to_string_lossy(),
+      "args": command.get_args().map(|arg| arg.to_string_lossy()).collect::<Vec<_>>(),
+      "cwd": cwd.map(|dir| dir.to_string_lossy().to_string()),
+      "stdin": payload,
+    })),
+  );
+}
+
+/// Adds the drift estimate to a result with both delays, and marks it
+/// `ResultStatus::Warning` past the request's `max_drift_ms_per_min`. The
+/// delays are measured in windows `duration - segment` apart (scaled by the
+/// anchor positions when there are any), so that's the span the drift is
+/// spread over, not the whole runtime. Leaves other results as they are.
+fn with_drift(app: &AppHandle, request: &SyncRequest, ffprobe: Option<&Path>, result: SyncResult) -> SyncResult {
+  let (Some(start), Some(end)) = (result.startDelay, result.endDelay) else {
+    return result;
+  };
+  let Some((video, _)) = request.pair_of(&result.videoFile, result.audio_path.as_deref()) else {
+    return result;
+  };
+  let probe = ffprobe.and_then(|ffprobe| probe_cached(app, ffprobe, video, request.low_priority).ok());
+  let Some(duration) = probe.as_ref().and_then(|probe| probe.duration).filter(|duration| *duration > 0.0) else {
+    return result;
+  };
+  let segment = result.segment_secs.unwrap_or_else(|| request.segment_for(video));
+  let Some((drift_ms_per_min, speed_factor)) = drift_of(start, end, duration, segment, &result.delays) else {
+    return result;
+  };
+  let video_fps = probe.as_ref().and_then(|probe| probe.frame_rate);
+  let speed_mismatch = speed_factor.and_then(|factor| speed_mismatch_of(factor, video_fps, &result.delays));
+  let limit = request.max_drift_ms_per_min.unwrap_or(DEFAULT_MAX_DRIFT_MS_PER_MIN);
+  let status = match result.status {
+    None if drift_ms_per_min.abs() > limit => Some(ResultStatus::Warning),
+    status => status,
+  };
+  SyncResult {
+    drift_ms_per_min: Some(drift_ms_per_min),
+    speed_factor,
+    speed_mismatch,
+    status,
+    ..result
+  }
+}
+
+/// Drift in ms per minute between the `start` and `end` delays of a video
+/// `duration` seconds long, and the audio speed factor it implies. `None`
+/// when both windows cover the same stretch, leaving nothing to spread the
+/// drift over.
+fn drift_of(start: f64, end: f64, duration: f64, segment: f64, delays: &[AnchorDelay]) -> Option<(f64, Option<f64>)> {
+  let positions = delays.iter().map(|delay| delay.position);
+  let (first, last) = if delays.len() >= 2 {
+    (positions.clone().fold(f64::INFINITY, f64::min), positions.fold(f64::NEG_INFINITY, f64::max))
+  } else {
+    (0.0, 1.0)
+  };
+  let span = (duration - segment).max(0.0) * (last - first);
+  if span <= 0.0 {
+    return None;
+  }
+  let drift_ms = end - start;
+  let span_ms = span * 1000.0;
+  // The audio covers `span_ms - drift_ms` of material over that span.
+  let speed_factor = (span_ms - drift_ms > 0.0).then(|| span_ms / (span_ms - drift_ms));
+  Some((drift_ms / (span / 60.0), speed_factor))
+}
+
+/// Warns with `sync-warning` about request options the bridge won't honour.
+/// `capabilities` is `None` for bridges that predate the `hello` handshake.
+fn warn_missing_capabilities(app: &AppHandle, request: &SyncRequest, capabilities: Option<&[String]>) {
+  for required in request.required_capabilities() {
+    let supported = capabilities.is_some_and(|capabilities| capabilities.iter().any(|name| name == required));
+    if !supported {
+      let _ = app.emit(
+        "sync-warning",
+        request.tagged(serde_json::json!({
+          "file": null,
+          "message": format!("The bridge doesn't support `{required}`; it will be ignored. Update the sidecar."),
+        })),
+      );
+    }
+  }
+}
+
+/// Batch-wide progress shared by bridge processes running side by side.
+struct BatchProgress {
+  processed: AtomicUsize,
+  total: usize,
+  clock: ProgressClock,
+  /// Set once one process has warned about missing bridge capabilities.
+  capabilities_checked: AtomicBool,
+  /// Results are retries of files already counted, so `processed` stays put.
+  retries: bool,
+}
+
+impl BatchProgress {
+  fn new(total: usize) -> Self {
+    Self {
+      processed: AtomicUsize::new(0),
+      total,
+      clock: ProgressClock::new(),
+      capabilities_checked: AtomicBool::new(false),
+      retries: false,
+    }
+  }
+
+  /// Progress for retrying files of a finished batch of `total`.
+  fn retries(total: usize) -> Self {
+    Self {
+      processed: AtomicUsize::new(total),
+      capabilities_checked: AtomicBool::new(true),
+      retries: true,
+      ..Self::new(total)
+    }
+  }
+
+  /// Counts one result, returning the files processed so far.
+  fn record(&self) -> usize {
+    if self.retries {
+      self.processed.load(Ordering::SeqCst)
+    } else {
+      self.processed.fetch_add(1, Ordering::SeqCst) + 1
+    }
+  }
+}
+
+/// Timing behind the `eta_secs` and `avg_file_ms` fields of `sync-progress`.
+struct ProgressClock {
+  started: Instant,
+  /// Sum and count of `file_end` elapsed times.
+  file_ms: Mutex<(u64, u64)>,
+}
+
+impl ProgressClock {
+  fn new() -> Self {
+    Self {
+      started: Instant::now(),
+      file_ms: Mutex::new((0, 0)),
+    }
+  }
+
+  fn record(&self, elapsed_ms: u64) {
+    if let Ok(mut file_ms) = self.file_ms.lock() {
+      file_ms.0 += elapsed_ms;
+      file_ms.1 += 1;
+    }
+  }
+
+  /// `sync-progress` payload. The ETA extrapolates wall time per processed
+  /// file, so it accounts for files running side by side.
+  fn payload(&self, processed: usize, total: usize, current: Option<&str>) -> serde_json::Value {
+    let avg_file_ms = self
+      .file_ms
+      .lock()
+      .ok()
+      .filter(|file_ms| file_ms.1 > 0)
+      .map(|file_ms| file_ms.0 / file_ms.1);
+    let eta_secs = (processed > 0).then(|| {
+      let per_file = self.started.elapsed().as_secs_f64() / processed as f64;
+      (per_file * total.saturating_sub(processed) as f64).round() as u64
+    });
+    serde_json::json!({
+      "processed": processed,
+      "total": total,
+      "current": current,
+      "eta_secs": eta_secs,
+      "avg_file_ms": avg_file_ms,
+    })
+  }
+}
+
+/// Splits the request into one-file requests and runs them on `concurrency`
+/// bridge processes, merging results back in the original order after the
+/// `prefilled` ones.
+fn run_sharded(
+  app: &AppHandle,
+  request: &SyncRequest,
+  cancel: Arc<AtomicBool>,
+  concurrency: usize,
+  prefilled: Vec<SyncResult>,
+) -> Result<Vec<SyncResult>, AppError> {
+  let shards = shard_request(request);
+  let batch = BatchProgress::new(shards.len());
+  emit_log(app, request.job_id, format!("Running {} files across {} workers.", shards.len(), concurrency));
+
+  // The first `fail_fast` failure; it cancels the other shards.
+  let failure = Mutex::new(None);
+  let outcomes = parallel_map(&shards, concurrency, |shard| {
+    if cancel.load(Ordering::SeqCst) {
+      return Vec::new();
+    }
+    match run_respawning(app, shard, &cancel, &batch) {
+      Ok(results) => results,
+      Err(AppError::Aborted { file, error, results }) => {
+        if let Ok(mut failure) = failure.lock() {
+          failure.get_or_insert((file, error));
+        }
+        results
+      }
+      Err(err) => shard_failure(shard, &err.to_string()),
+    }
+  });
+
+  let results: Vec<SyncResult> = prefilled.into_iter().chain(outcomes.into_iter().flatten()).collect();
+  if let Some((file, error)) = failure.into_inner().ok().flatten() {
+    return Err(AppError::Aborted { file, error, results });
+  }
+  let results = retry_failed(app, request, &cancel, results);
+  if cancel.load(Ordering::SeqCst) {
+    emit_sync_canceled(app, request, &results);
+  } else {
+    emit_sync_done(app, request, &results);
+  }
+  Ok(results)
+}
+
+/// Results `retry_failed` runs again: bridge errors and low confidence.
+fn needs_retry(result: &SyncResult) -> bool {
+  match result.status {
+    None => result.error.is_some(),
+    Some(status) => status == ResultStatus::LowConfidence,
+  }
+}
+
+/// Runs each pair that `needs_retry` again with the `retry_escalation`
+/// durations longer than its own segment, one at a time until a run
+/// succeeds, keeping only the last outcome and emitting its `sync-result`.
+/// A run that fails outright counts as an attempt too. Stops retrying on
+/// cancel.
+fn retry_failed(app: &AppHandle, request: &SyncRequest, cancel: &Arc<AtomicBool>, results: Vec<SyncResult>) -> Vec<SyncResult> {
+  let Some(steps) = request.retry_escalation.as_ref().filter(|steps| !steps.is_empty()) else {
+    return results;
+  };
+  let batch = BatchProgress::retries(request.pairs.as_ref().map_or(0, Vec::len));
+  results
+    .into_iter()
+    .map(|result| {
+      let Some((video, audio)) = request.retry_pair(&result).cloned() else {
+        return result;
+      };
+      let first = request.segment_for(&video);
+      let mut last = SyncResult {
+        attempts: Some(1),
+        segment_secs: Some(first),
+        ..result
+      };
+      for &segment in steps.iter().filter(|&&segment| segment > first) {
+        if cancel.load(Ordering::SeqCst) {
+          break;
+        }
+        emit_log(app, request.job_id, format!("Retrying {} with a {segment}s segment", last.videoFile));
+        let retry = request.retry_of(&video, &audio, segment);
+        let failed = |error: String| SyncResult {
+          video_path: Some(video.clone()),
+          audio_path: Some(audio.clone()),
+          ..SyncResult::failed(last.videoFile.clone(), last.audioFile.clone(), error)
+        };
+        let outcome = match run_bridge_process(app, &retry, cancel.clone(), Some(&batch)) {
+          Ok(results) => match results.into_iter().next() {
+            Some(outcome) => outcome,
+            // Canceled before the bridge reported the file.
+            None if cancel.load(Ordering::SeqCst) => break,
+            None => failed("The bridge returned no result".to_string()),
+          },
+          Err(err) => failed(err.to_string()),
+        };
+        last = SyncResult {
+          attempts: last.attempts.map(|attempts| attempts + 1),
+          segment_secs: Some(segment),
+          ..outcome
+        };
+        if !needs_retry(&last) {
+          break;
+        }
+      }
+      let _ = app.emit("sync-result", request.tagged(&last));
+      last
+    })
+    .collect()
+}
+
+/// One request per pair of an already resolved request.
+fn shard_request(request: &SyncRequest) -> Vec<SyncRequest> {
+  request
+    .pairs
+    .iter()
+    .flatten()
+    .enumerate()
+    .map(|(index, pair)| SyncRequest {
+      pairs: Some(vec![pair.clone()]),
+      pair_confidence: request
+        .pair_confidence
+        .as_ref()
+        .and_then(|confidence| confidence.get(index))
+        .map(|&confidence| vec![confidence]),
+      concurrency: Some(1),
+      ..request.clone()
+    })
+    .collect()
+}
+
+/// Fills in `pairs` when the request doesn't carry them, so the bridge syncs
+/// exactly the plan `preview_matches` shows, overrides included. Planning
+/// notes go to `sync-log`.
+fn resolve_pairs(app: &AppHandle, mut request: SyncRequest) -> Result<SyncRequest, String> {
+  if request.pairs.is_none() || request.pair_overrides.is_some() {
+    let plan = plan_pairs(app, &request)?;
+    for note in &plan.notes {
+      emit_log(app, request.job_id, note);
+    }
+    for pair in &plan.needs_confirmation {
+      emit_log(app, request.job_id, format!(
+          "Skipping {} ↔ {}: confidence {} needs confirmation",
+          file_display_name(Path::new(&pair.video)),
+          file_display_name(Path::new(&pair.audio)),
+          pair.confidence
+        ));
+    }
+    request.pair_confidence = plan.confidence();
+    request.pairs = Some(plan.pairs.into_iter().map(|pair| (pair.video, pair.audio)).collect());
+  }
+  Ok(request)
+}
+
+/// A proposed `(video, audio)` pair; `confidence` is set by fuzzy matching.
+#[derive(Debug, Clone, Serialize)]
+struct PlannedPair {
+  video: String,
+  audio: String,
+  confidence: Option<u8>,
+}
+
+impl PlannedPair {
+  fn new((video, audio): (String, String)) -> Self {
+    Self { video, audio, confidence: None }
+  }
+}
+
+#[derive(Debug, Default)]
+struct PairPlan {
+  pairs: Vec<PlannedPair>,
+  /// Fuzzy pairs under `min_confidence`, left out of the run.
+  needs_confirmation: Vec<FuzzyPair>,
+  /// Files the duration strategy couldn't tell apart; they need manual pairs.
+  manual_groups: Vec<ManualGroup>,
+  /// Tags the metadata strategy read, by path.
+  tags: HashMap<String, MediaTags>,
+  /// Why files were left out.
+  notes: Vec<String>,
+}
+
+impl PairPlan {
+  /// Per-pair confidence for `SyncRequest.pair_confidence`, if every pair has one.
+  fn confidence(&self) -> Option<Vec<u8>> {
+    if self.pairs.is_empty() {
+      return None;
+    }
+    self.pairs.iter().map(|pair| pair.confidence).collect()
+  }
+}
+
+/// The pairs a request would sync. Series folders are matched per
+/// `match_strategy`; the default mirrors the bridge's own `find_matching_files`.
+fn plan_pairs(app: &AppHandle, request: &SyncRequest) -> Result<PairPlan, String> {
+  let strategy = request.match_strategy.as_deref().unwrap_or("default");
+  if !matches!(strategy, "" | "default" | "season_episode" | "fuzzy" | "duration" | "metadata" | "pattern") {
+    return Err(format!("Unknown match strategy: {strategy}"));
+  }
+  let mut plan = PairPlan::default();
+  if let Some(pairs) = &request.pairs {
+    // Explicit pairs are taken as chosen, with whatever confidence came along.
+    let confidence = request.pair_confidence.clone().unwrap_or_default();
+    plan.pairs = pairs
+      .iter()
+      .enumerate()
+      .map(|(index, pair)| PlannedPair {
+        confidence: confidence.get(index).copied(),
+        ..PlannedPair::new(pair.clone())
+      })
+      .collect();
+    apply_overrides(&mut plan, request)?;
+    return Ok(plan);
+  }
+
+  if request.mode == "movie" {
+    // Folders list the way the picker showed them, globs and size floor included.
+    let options = ListOptions {
+      include_glob: request.include_glob.clone(),
+      exclude_glob: request.exclude_glob.clone(),
+      ..ListOptions::default()
+    }
+    .with_saved_min_size(app);
+    let videos = match &request.video_files {
+      Some(files) if !files.is_empty() => files.clone(),
+      _ => request_video_folders(request)
+        .iter()
+        .flat_map(|folder| list_movie_videos(Path::new(folder), &options).files)
+        .map(|item| item.path)
+        .collect(),
+    };
+    let audios = request.audio_file.clone().unwrap_or_default();
+    plan.pairs = videos
+      .iter()
+      .flat_map(|video| audios.iter().map(move |audio| PlannedPair::new((video.clone(), audio.clone()))))
+      .collect();
+  } else {
+    let min_confidence = request.min_confidence.unwrap_or(DEFAULT_MIN_CONFIDENCE).min(100);
+    // Each audio source is matched on its own, giving a video up to one pair per source.
+    let folders: Vec<(String, String)> = request
+      .audio_sources()
+      .into_iter()
+      .flat_map(|source| {
+        request_video_folders(request)
+          .into_iter()
+          .map(move |video| (video, source.folder.clone()))
+      })
+      .collect();
+    for (folder, audio_folder) in folders {
+      let (video_folder, audio_folder) = (Path::new(&folder), Path::new(&audio_folder));
+      let pattern = request.match_pattern.as_deref();
+      let extensions = request.audio_extensions.as_deref();
+      match strategy {
+        "pattern" => plan.pairs.extend(
+          match_pattern_key_pairs(video_folder, audio_folder, extensions, pattern, &mut plan.notes)?
+            .into_iter()
+            .map(PlannedPair::new),
+        ),
+        "season_episode" => plan.pairs.extend(
+          match_season_episode_pairs(video_folder, audio_folder, extensions, pattern, &mut plan.notes)?
+            .into_iter()
+            .map(PlannedPair::new),
+        ),
+        "metadata" => plan.pairs.extend(
+          match_metadata_pairs(app, request, video_folder, audio_folder, &mut plan.notes, &mut plan.tags)?
+            .into_iter()
+            .map(PlannedPair::new),
+        ),
+        "duration" => {
+          let tolerance = request.duration_tolerance_secs.unwrap_or(DEFAULT_DURATION_TOLERANCE_SECS).max(0.0);
+          let matched = match_duration_pairs(app, video_folder, audio_folder, extensions, tolerance, request.low_priority, &mut plan.notes)?;
+          plan.pairs.extend(matched.pairs.into_iter().map(PlannedPair::new));
+          plan.manual_groups.extend(matched.manual_groups);
+        }
+        "fuzzy" => {
+          for pair in match_fuzzy_pairs(video_folder, audio_folder, extensions, pattern)? {
+            if pair.confidence >= min_confidence || request.accept_low_confidence {
+              plan.pairs.push(PlannedPair {
+                confidence: Some(pair.confidence),
+                ..PlannedPair::new((pair.video, pair.audio))
+              });
+            } else {
+              plan.needs_confirmation.push(pair);
+            }
+          }
+        }
+        _ => plan.pairs.extend(
+          match_series_pairs(video_folder, audio_folder, extensions, pattern, &mut plan.notes)?
+            .into_iter()
+            .map(PlannedPair::new),
+        ),
+      }
+    }
+  }
+
+  // Derived pairs honour the name globs.
+  let globs = NameGlobs::new(request.include_glob.as_deref(), request.exclude_glob.as_deref())?;
+  let allowed = |video: &str| globs.matches(&file_display_name(Path::new(video)));
+  plan.pairs.retain(|pair| allowed(&pair.video));
+  plan.needs_confirmation.retain(|pair| allowed(&pair.video));
+  apply_overrides(&mut plan, request)?;
+  Ok(plan)
+}
+
+/// Replaces planned pairs with `pair_overrides`. An override takes over its
+/// video's slot (or is appended) and claims its audio from any other pair.
+/// Overrides naming a missing audio file fail the whole plan.
+fn apply_overrides(plan: &mut PairPlan, request: &SyncRequest) -> Result<(), String> {
+  let Some(overrides) = &request.pair_overrides else {
+    return Ok(());
+  };
+  for entry in overrides {
+    if !Path::new(&entry.audio).is_file() {
+      return Err(format!(
+        "Override for {}: audio file not found: {}",
+        file_display_name(Path::new(&entry.video)),
+        entry.audio
+      ));
+    }
+  }
+  for entry in overrides {
+    plan.pairs.retain(|pair| pair.audio != entry.audio || pair.video == entry.video);
+    plan
+      .needs_confirmation
+      .retain(|pair| pair.video != entry.video && pair.audio != entry.audio);
+    let pair = PlannedPair::new((entry.video.clone(), entry.audio.clone()));
+    match plan.pairs.iter().position(|planned| planned.video == entry.video) {
+      Some(index) => plan.pairs[index] = pair,
+      None => plan.pairs.push(pair),
+    }
+  }
+  Ok(())
+}
+
+/// Previews a request without spawning the bridge: emits the plan as
+/// `sync-plan`, then the usual per-file events with a delay-less
+/// `ResultStatus::Planned` row per pair, and `sync-done` after `prefilled`.
+/// Pairs whose durations differ by more than `duration_tolerance_secs` get
+/// a `sync-warning` and the difference as the row's error.
+fn run_dry(
+  app: &AppHandle,
+  request: &SyncRequest,
+  cancel: &AtomicBool,
+  prefilled: Vec<SyncResult>,
+) -> Result<Vec<SyncResult>, AppError> {
+  let pairs = request.pairs.clone().unwrap_or_default();
+  let confidence = request.pair_confidence.clone().unwrap_or_default();
+  let basename = |path: &str| file_display_name(Path::new(path));
+  let plan: Vec<_> = pairs
+    .iter()
+    .enumerate()
+    .map(|(index, (video, audio))| serde_json::json!({ "video": video, "audio": audio, "confidence": confidence.get(index) }))
+    .collect();
+  let _ = app.emit("sync-plan", request.tagged(serde_json::json!({ "pairs": plan })));
+  let ffprobe = resolve_ffprobe(app).ok();
+  if ffprobe.is_none() {
+    emit_log(app, request.job_id, "ffprobe not found; skipping the duration check.");
+  }
+  let tolerance = request.duration_tolerance_secs.unwrap_or(DEFAULT_DURATION_TOLERANCE_SECS).max(0.0);
+  let duration = |path: &str| ffprobe.as_deref().and_then(|ffprobe| probe_cached(app, ffprobe, path, request.low_priority).ok()?.duration);
+  let clock = ProgressClock::new();
+  let mut results = prefilled;
+  for (index, (video, audio)) in pairs.iter().enumerate() {
+    if cancel.load(Ordering::SeqCst) {
+      emit_sync_canceled(app, request, &results);
+      return Ok(results);
+    }
+    let file = basename(video);
+    let _ = app.emit("sync-progress", request.tagged(clock.payload(index, pairs.len(), Some(&file))));
+    let _ = app.emit("sync-file-start", request.tagged(serde_json::json!({ "file": file })));
+    let started = Instant::now();
+    let error = match (duration(video), duration(audio)) {
+      (Some(video_secs), Some(audio_secs)) if (video_secs - audio_secs).abs() > tolerance => {
+        let message = format!("durations differ by {:.1}s", (video_secs - audio_secs).abs());
+        let _ = app.emit("sync-warning", request.tagged(serde_json::json!({ "file": video, "message": message })));
+        Some(message)
+      }
+      _ => None,
+    };
+    let (video_language, audio_language) = request.languages_of(&file, Some(audio));
+    let result = SyncResult {
+      error,
+      match_confidence: confidence.get(index).copied(),
+      sample_rate: request.sample_rate,
+      video_stream: request.video_stream_for(video),
+      audio_stream: request.audio_stream_for(video),
+      video_language,
+      audio_language,
+      algorithm: request.algorithm.clone(),
+      offset_hint_seconds: request.offset_hint_for(video),
+      downmix: request.downmix_for(video),
+      status: Some(ResultStatus::Planned),
+      video_path: Some(video.clone()),
+      audio_path: Some(audio.clone()),
+      ..SyncResult::unmatched(file.clone(), basename(audio), request.source_label(audio))
+    };
+    results.push(result.clone());
+    let _ = app.emit("sync-result", request.tagged(result));
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    clock.record(elapsed_ms);
+    let _ = app.emit(
+      "sync-file-end",
+      request.tagged(serde_json::json!({ "file": file, "elapsed_ms": elapsed_ms })),
+    );
+  }
+  let _ = app.emit("sync-progress", request.tagged(clock.payload(pairs.len(), pairs.len(), None)));
+  emit_sync_done(app, request, &results);
+  Ok(results)
+}
+
+/// Error rows for a shard whose bridge process failed outright.
+fn shard_failure(shard: &SyncRequest, err: &str) -> Vec<SyncResult> {
+  let basename = |path: &str| file_display_name(Path::new(path));
+  shard
+    .pairs
+    .iter()
+    .flatten()
+    .map(|(video, audio)| SyncResult {
+      match_confidence: shard.confidence_of(&basename(video), &basename(audio)),
+      source_label: shard.source_label(audio),
+      video_path: Some(video.clone()),
+      audio_path: Some(audio.clone()),
+      ..SyncResult::failed(basename(video), basename(audio), err.to_string())
+    })
+    .collect()
+}
+
+fn request_video_folders(request: &SyncRequest) -> Vec<String> {
+  match &request.video_folders {
+    Some(folders) if !folders.is_empty() => folders.clone(),
+    _ => request.video_folder.iter().cloned().collect(),
+  }
+}
+
+/// Built-in season/episode patterns, tried in the same order as the bridge.
+const DEFAULT_MATCH_PATTERNS: [&str; 3] = [r"[Ss](\d+)[Ee](\d+)", r"(\d+)x(\d+)", r"[._\s-](\d{1,3})[._\s-]"];
+
+type MatchKey = Vec<Option<String>>;
+
+/// Season/episode tag parsed from a file name. Multi-episode files
+/// (`S01E01E02`) carry every episode and are treated as ambiguous.
+#[derive(Debug, Clone, PartialEq)]
+struct EpisodeTag {
+  season: Option<u32>,
+  episodes: Vec<u32>,
+}
+
+impl EpisodeTag {
+  /// Pairing key; a missing season counts as season 1.
+  fn key(&self) -> (u32, u32) {
+    (self.season.unwrap_or(1), self.episodes[0])
+  }
+
+  fn is_ambiguous(&self) -> bool {
+    self.episodes.len() > 1
+  }
+}
+
+/// Built-in episode markers, most specific first: `S01E02`/`s1e2` (with
+/// `S01E01E02` multi-episode runs), `01x02`, a bare `E02`, then an absolute
+/// episode number standing alone between separators.
+fn builtin_episode_patterns() -> &'static [Regex] {
+  static PATTERNS: std::sync::OnceLock<Vec<Regex>> = std::sync::OnceLock::new();
+  PATTERNS.get_or_init(|| {
+    [
+      r"(?i)s(\d{1,3})((?:[ ._-]?e\d{1,4})+)",
+      r"(?i)\b(\d{1,2})x(\d{1,3})\b",
+      r"(?i)(?:^|[^a-z0-9])ep?(\d{1,4})(?:[^0-9]|$)",
+      r"(?:^|[ ._-])(\d{1,3})(?:[ ._-]|$)",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("built-in episode pattern is valid"))
+    .collect()
+  })
+}
+
+/// Runs of ASCII digits.
+fn digit_runs() -> &'static Regex {
+  static DIGITS: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+  DIGITS.get_or_init(|| Regex::new(r"\d+").expect("digit pattern is valid"))
+}
+
+/// Reads an episode tag from `name`, trying `custom` first. A custom pattern's
+/// first two groups are season and episode; a single group is the episode.
+fn parse_episode(name: &str, custom: Option<&Regex>) -> Option<EpisodeTag> {
+  parse_episode_with(name, custom, true)
+}
+
+/// Whether the names of one folder may fall back to bare absolute episode
+/// numbers: only when none carries an `S01E02` tag, so next to tagged
+/// episodes a stray number (a special's, a year's) can't pose as one.
+fn allows_bare_numbers<'a>(names: impl IntoIterator<Item = &'a str>) -> bool {
+  let season_episode = &builtin_episode_patterns()[0];
+  !names.into_iter().any(|name| season_episode.is_match(name))
+}
+
+/// `parse_episode`, skipping the bare-number fallback unless `bare_numbers`.
+fn parse_episode_with(name: &str, custom: Option<&Regex>, bare_numbers: bool) -> Option<EpisodeTag> {
+  let number = |text: &str| text.parse::<u32>().ok();
+  if let Some(caps) = custom.and_then(|pattern| pattern.captures(name)) {
+    let group = |index: usize| caps.get(index).and_then(|m| number(m.as_str()));
+    match (group(1), group(2)) {
+      (Some(season), Some(episode)) => return Some(EpisodeTag { season: Some(season), episodes: vec![episode] }),
+      (Some(episode), None) => return Some(EpisodeTag { season: None, episodes: vec![episode] }),
+      _ => {}
+    }
+  }
+
+  let patterns = builtin_episode_patterns();
+  if let Some(caps) = patterns[0].captures(name) {
+    let episodes: Vec<u32> = digit_runs().find_iter(&caps[2]).filter_map(|m| number(m.as_str())).collect();
+    if !episodes.is_empty() {
+      return Some(EpisodeTag { season: number(&caps[1]), episodes });
+    }
+  }
+  if let Some(caps) = patterns[1].captures(name) {
+    return Some(EpisodeTag { season: number(&caps[1]), episodes: vec![number(&caps[2])?] });
+  }
+  let fallbacks = if bare_numbers { &patterns[2..] } else { &patterns[2..3] };
+  fallbacks.iter().find_map(|pattern| {
+    let caps = pattern.captures(name)?;
+    Some(EpisodeTag { season: None, episodes: vec![number(&caps[1])?] })
+  })
+}
+
+fn compile_match_pattern(match_pattern: Option<&str>) -> Result<Option<Regex>, String> {
+  match_pattern
+    .filter(|pattern| !pattern.is_empty())
+    .map(|pattern| Regex::new(&nfc(pattern)).map_err(|err| format!("Invalid match pattern: {err}")))
+    .transpose()
+}
+
+#[derive(Debug, Serialize)]
+struct PatternError {
+  message: String,
+  /// Character range in the pattern the error points at, when known.
+  start: Option<usize>,
+  end: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct PatternSample {
+  name: String,
+  matched: bool,
+  /// Capture groups 1.., `None` where a group didn't take part in the match.
+  groups: Vec<Option<String>>,
+  /// The key the `"pattern"` match strategy would pair this name on.
+  key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PatternCheck {
+  valid: bool,
+  error: Option<PatternError>,
+  samples: Vec<PatternSample>,
+  warnings: Vec<String>,
+}
+
+/// Compiles a `match_pattern` the way sync planning does and runs it over
+/// `sample_names`, so the UI can show errors and captures as the user types.
+#[tauri::command]
+fn validate_pattern(pattern: String, sample_names: Vec<String>) -> PatternCheck {
+  let chars = |byte: usize| pattern[..byte.min(pattern.len())].chars().count();
+  let regex = match Regex::new(&nfc(&pattern)) {
+    Ok(regex) => regex,
+    Err(err) => {
+      // The AST parser reports where the error is; `regex` only says what.
+      let error = match regex_syntax::ast::parse::Parser::new().parse(&pattern) {
+        Err(ast_err) => PatternError {
+          message: ast_err.kind().to_string(),
+          start: Some(chars(ast_err.span().start.offset)),
+          end: Some(chars(ast_err.span().end.offset)),
+        },
+        Ok(_) => PatternError {
+          message: err.to_string(),
+          start: None,
+          end: None,
+        },
+      };
+      return PatternCheck {
+        valid: false,
+        error: Some(error),
+        samples: Vec::new(),
+        warnings: Vec::new(),
+      };
+    }
+  };
+
+  let samples: Vec<PatternSample> = sample_names
+    .into_iter()
+    .map(|name| {
+      let normalized = nfc(&name);
+      let caps = regex.captures(&normalized);
+      PatternSample {
+        matched: caps.is_some(),
+        groups: (1..regex.captures_len())
+          .map(|index| caps.as_ref().and_then(|caps| caps.get(index)).map(|m| m.as_str().to_string()))
+          .collect(),
+        key: pattern_key(&regex, &normalized),
+        name,
+      }
+    })
+    .collect();
+
+  let mut warnings = Vec::new();
+  if regex.captures_len() == 1 {
+    warnings.push("Pattern has no capture groups, so every match has the same key".to_string());
+  } else if !samples.is_empty() && samples.iter().all(|sample| sample.groups.iter().all(Option::is_none)) {
+    warnings.push("Pattern captures nothing from any sample name".to_string());
+  }
+  PatternCheck {
+    valid: true,
+    error: None,
+    samples,
+    warnings,
+  }
+}
+
+/// A listed file as `(name, path)`.
+type MediaEntry = (String, String);
+
+/// Files directly inside `folder` as `(name, path)` that `keep` admits, in
+/// natural order.
+fn media_entries(folder: &Path, keep: impl Fn(&Path) -> bool) -> Vec<(String, String)> {
+  let mut entries: Vec<(String, String)> = folder_entries(folder)
+    .into_iter()
+    .filter(|(_, path)| keep(Path::new(path)))
+    .collect();
+  entries.sort_by(|a, b| natural_cmp(&a.0, &b.0));
+  entries
+}
+
+/// The `(name, path)` entries pairing draws from: the videos in
+/// `video_folder` and the files `audio_extensions` admits in `audio_folder`.
+/// A folder serving both sides keeps its videos on the video side.
+fn pairing_entries(
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+) -> (Vec<MediaEntry>, Vec<MediaEntry>) {
+  let videos = media_entries(video_folder, |path| media_kind(path) == Some("video"));
+  let video_paths: HashSet<&str> = videos.iter().map(|(_, path)| path.as_str()).collect();
+  let audios = media_entries(audio_folder, |path| {
+    accepts_audio(path, audio_extensions) && !video_paths.contains(path.to_string_lossy().as_ref())
+  });
+  (videos, audios)
+}
+
+/// The pairing key `pattern` extracts from `name`: its `key` named group
+/// when it has one, else the first capture group, else the whole match.
+fn pattern_key(pattern: &Regex, name: &str) -> Option<String> {
+  let caps = pattern.captures(name)?;
+  let key = match pattern.capture_names().flatten().any(|group| group == "key") {
+    true => caps.name("key"),
+    false => caps.get(1).or_else(|| caps.get(0)),
+  }?;
+  Some(key.as_str().trim().to_string()).filter(|key| !key.is_empty())
+}
+
+/// `entries` by `key`, for pairing. Files without a key (noted as
+/// `missing`), or whose `what` an earlier file already has, are noted and
+/// left out; Python's dicts would keep whichever came last instead.
+fn key_entries<K: Eq + std::hash::Hash>(
+  entries: Vec<MediaEntry>,
+  key: impl Fn(&str) -> Option<K>,
+  what: &str,
+  missing: &str,
+  notes: &mut Vec<String>,
+) -> HashMap<K, String> {
+  let mut keys: HashMap<K, MediaEntry> = HashMap::new();
+  for (name, path) in entries {
+    match key(&name) {
+      Some(key) => match keys.entry(key) {
+        std::collections::hash_map::Entry::Vacant(slot) => {
+          slot.insert((name, path));
+        }
+        std::collections::hash_map::Entry::Occupied(slot) => {
+          notes.push(format!("Skipping {name}: {} has the same {what}", slot.get().0));
+        }
+      },
+      None => notes.push(format!("Skipping {name}: {missing}")),
+    }
+  }
+  keys.into_iter().map(|(key, (_, path))| (key, path)).collect()
+}
+
+/// Pairs media files with equal `pattern_key`s. Files without a key, or
+/// repeating a key already seen, are noted and left out.
+fn match_pattern_key_pairs(
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+  match_pattern: Option<&str>,
+  notes: &mut Vec<String>,
+) -> Result<Vec<(String, String)>, String> {
+  let pattern = compile_match_pattern(match_pattern)?.ok_or_else(|| "The pattern match strategy needs a match_pattern".to_string())?;
+  let key = |name: &str| pattern_key(&pattern, name);
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, audio_extensions);
+  let videos = key_entries(videos, key, "key", "the match pattern finds no key", notes);
+  let audios = key_entries(audios, key, "key", "the match pattern finds no key", notes);
+
+  let mut pairs = Vec::new();
+  for (key, video) in &videos {
+    match audios.get(key) {
+      Some(audio) => pairs.push((video.clone(), audio.clone())),
+      None => notes.push(format!("No audio for key \"{key}\" ({})", file_display_name(Path::new(video)))),
+    }
+  }
+  pairs.sort();
+  Ok(pairs)
+}
+
+/// Pairs media files by `(season, episode)`, ignoring listing order. Files
+/// without a marker, or repeating an episode already seen, are noted and
+/// left out rather than guessed at.
+fn match_season_episode_pairs(
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+  match_pattern: Option<&str>,
+  notes: &mut Vec<String>,
+) -> Result<Vec<(String, String)>, String> {
+  let custom = compile_match_pattern(match_pattern)?;
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, audio_extensions);
+  Ok(pair_entries_by_episode(videos, audios, custom.as_ref(), notes))
+}
+
+/// `match_season_episode_pairs` over `(name, path)` entries.
+fn pair_entries_by_episode(
+  videos: Vec<(String, String)>,
+  audios: Vec<(String, String)>,
+  custom: Option<&Regex>,
+  notes: &mut Vec<String>,
+) -> Vec<(String, String)> {
+  let mut keyed = |entries: Vec<(String, String)>| {
+    let bare_numbers = allows_bare_numbers(entries.iter().map(|(name, _)| name.as_str()));
+    let key = |name: &str| parse_episode_with(name, custom, bare_numbers).map(|tag| tag.key());
+    key_entries(entries, key, "episode", "no season/episode marker", notes)
+  };
+  let videos = keyed(videos);
+  let audios = keyed(audios);
+
+  let mut pairs = Vec::new();
+  for (key, video) in &videos {
+    match audios.get(key) {
+      Some(audio) => pairs.push((video.clone(), audio.clone())),
+      None => notes.push(format!(
+        "No audio for S{:02}E{:02} ({})",
+        key.0,
+        key.1,
+        file_display_name(Path::new(video))
+      )),
+    }
+  }
+  pairs.sort();
+  pairs
+}
+
+/// Pairs files on their `show`/`season`/`episode` tags (a missing season
+/// counts as 1; shows must agree when both sides name one), then pairs the
+/// rest by file name as `match_season_episode_pairs` does. The tags read
+/// are added to `tags`.
+fn match_metadata_pairs(
+  app: &AppHandle,
+  request: &SyncRequest,
+  video_folder: &Path,
+  audio_folder: &Path,
+  notes: &mut Vec<String>,
+  tags: &mut HashMap<String, MediaTags>,
+) -> Result<Vec<(String, String)>, String> {
+  let custom = compile_match_pattern(request.match_pattern.as_deref())?;
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, request.audio_extensions.as_deref());
+  let Ok(ffprobe) = resolve_ffprobe(app) else {
+    notes.push("ffprobe not found; matching by file name only".to_string());
+    return Ok(pair_entries_by_episode(videos, audios, custom.as_ref(), notes));
+  };
+  let read_tags = |entries: &[(String, String)]| {
+    parallel_map(entries, PROBE_WORKERS, |(_, path)| {
+      probe_cached(app, &ffprobe, path, request.low_priority).map(|probe| probe.tags).unwrap_or_default()
+    })
+  };
+  let video_tags = read_tags(&videos);
+  let audio_tags = read_tags(&audios);
+  let show_key = |tags: &MediaTags| tags.show.as_deref().map(|show| nfc(show).to_lowercase());
+
+  let mut audio_used = vec![false; audios.len()];
+  let mut video_used = vec![false; videos.len()];
+  let mut pairs = Vec::new();
+  for (video, video_tag) in video_tags.iter().enumerate() {
+    let Some(episode) = video_tag.episode else {
+      continue;
+    };
+    let key = (video_tag.season.unwrap_or(1), episode);
+    let found = audio_tags.iter().enumerate().position(|(audio, audio_tag)| {
+      !audio_used[audio]
+        && audio_tag.episode.map(|episode| (audio_tag.season.unwrap_or(1), episode)) == Some(key)
+        && match (show_key(video_tag), show_key(audio_tag)) {
+          (Some(video_show), Some(audio_show)) => video_show == audio_show,
+          _ => true,
+        }
+    });
+    if let Some(audio) = found {
+      audio_used[audio] = true;
+      video_used[video] = true;
+      pairs.push((videos[video].1.clone(), audios[audio].1.clone()));
+    }
+  }
+
+  for ((_, path), file_tags) in videos.iter().zip(video_tags).chain(audios.iter().zip(audio_tags)) {
+    if file_tags != MediaTags::default() {
+      tags.insert(path.clone(), file_tags);
+    }
+  }
+  let rest = |entries: Vec<(String, String)>, used: &[bool]| {
+    entries
+      .into_iter()
+      .zip(used)
+      .filter(|(_, used)| !**used)
+      .map(|(entry, _)| entry)
+      .collect::<Vec<_>>()
+  };
+  pairs.extend(pair_entries_by_episode(
+    rest(videos, &video_used),
+    rest(audios, &audio_used),
+    custom.as_ref(),
+    notes,
+  ));
+  pairs.sort();
+  Ok(pairs)
+}
+
+/// The videos and audio files matching could draw from when a request
+/// doesn't carry explicit pairs, each side holding only its own kind of
+/// file, so a folder shared by both splits by extension.
+fn candidate_inputs(request: &SyncRequest) -> Result<(Vec<String>, Vec<String>), String> {
+  if request.mode == "movie" {
+    return Ok((Vec::new(), request.audio_file.clone().unwrap_or_default()));
+  }
+  let globs = NameGlobs::new(request.include_glob.as_deref(), request.exclude_glob.as_deref())?;
+  let videos: Vec<(String, String)> = request_video_folders(request)
+    .iter()
+    .flat_map(|folder| media_entries(Path::new(folder), |path| media_kind(path) == Some("video")))
+    .collect();
+  let video_paths: HashSet<&str> = videos.iter().map(|(_, path)| path.as_str()).collect();
+  let audios: Vec<String> = request
+    .audio_sources()
+    .iter()
+    .flat_map(|source| {
+      media_entries(Path::new(&source.folder), |path| {
+        accepts_audio(path, request.audio_extensions.as_deref()) && !video_paths.contains(path.to_string_lossy().as_ref())
+      })
+    })
+    .map(|(_, path)| path)
+    .collect();
+  let videos = videos
+    .into_iter()
+    .filter(|(name, _)| globs.matches(name))
+    .map(|(_, path)| path)
+    .collect();
+  Ok((videos, audios))
+}
+
+/// Result rows for series files no pair uses, announced up front with
+/// `sync-unmatched`.
+fn unmatched_rows(app: &AppHandle, request: &SyncRequest) -> Result<Vec<SyncResult>, String> {
+  let (videos, audios) = candidate_inputs(request)?;
+  let paired: HashSet<&String> = request
+    .pairs
+    .iter()
+    .flatten()
+    .flat_map(|(video, audio)| [video, audio])
+    .collect();
+  let videos: Vec<String> = videos.into_iter().filter(|path| !paired.contains(path)).collect();
+  let audios: Vec<String> = audios.into_iter().filter(|path| !paired.contains(path)).collect();
+  if videos.is_empty() && audios.is_empty() {
+    return Ok(Vec::new());
+  }
+  let _ = app.emit("sync-unmatched", request.tagged(serde_json::json!({ "videos": videos, "audios": audios })));
+
+  let name = |path: &str| file_display_name(Path::new(path));
+  let rows = videos
+    .iter()
+    .map(|video| SyncResult::unmatched(name(video), String::new(), None))
+    .chain(
+      audios
+        .iter()
+        .map(|audio| SyncResult::unmatched(String::new(), name(audio), request.source_label(audio))),
+    )
+    .collect();
+  Ok(rows)
+}
+
+#[derive(Debug, Serialize)]
+struct PreviewPair {
+  video: String,
+  audio: String,
+  /// Either side names several episodes, e.g. `S01E01E02`.
+  ambiguous: bool,
+  /// Fuzzy match score, 0–100.
+  confidence: Option<u8>,
+  /// Scored under `min_confidence`; left out of the run unless accepted.
+  needs_confirmation: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MatchPreview {
+  pairs: Vec<PreviewPair>,
+  unmatched_videos: Vec<String>,
+  unmatched_audio: Vec<String>,
+  /// Why files were left out, as the sync would log them.
+  notes: Vec<String>,
+  /// Files the duration strategy couldn't tell apart.
+  manual_groups: Vec<ManualGroup>,
+  /// Tags the metadata strategy read, by path, for files that had any.
+  tags: HashMap<String, MediaTags>,
+}
+
+/// Shows the pairing a sync would run, using the same planning `start_sync`
+/// hands the bridge, plus the media files no pair uses.
+#[tauri::command]
+async fn preview_matches(app: AppHandle, request: SyncRequest) -> Result<MatchPreview, AppError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let plan = plan_pairs(&app, &request).map_err(AppError::InvalidRequest)?;
+    let custom = compile_match_pattern(request.match_pattern.as_deref()).map_err(AppError::InvalidRequest)?;
+    let ambiguous = |path: &str| {
+      parse_episode(&file_display_name(Path::new(path)), custom.as_ref()).is_some_and(|tag| tag.is_ambiguous())
+    };
+
+    let (videos, audios) = if request.pairs.is_some() {
+      (Vec::new(), Vec::new())
+    } else {
+      candidate_inputs(&request).map_err(AppError::InvalidRequest)?
+    };
+    let proposed = plan.pairs.into_iter().map(|pair| (pair, false)).chain(
+      plan
+        .needs_confirmation
+        .into_iter()
+        .map(|pair| (PlannedPair { video: pair.video, audio: pair.audio, confidence: Some(pair.confidence) }, true)),
+    );
+    let pairs: Vec<PreviewPair> = proposed
+      .map(|(pair, needs_confirmation)| PreviewPair {
+        ambiguous: ambiguous(&pair.video) || ambiguous(&pair.audio),
+        video: pair.video,
+        audio: pair.audio,
+        confidence: pair.confidence,
+        needs_confirmation,
+      })
+      .collect();
+    let used_videos: HashSet<&String> = pairs.iter().map(|pair| &pair.video).collect();
+    let used_audio: HashSet<&String> = pairs.iter().map(|pair| &pair.audio).collect();
+    let unmatched_videos = videos.iter().filter(|path| !used_videos.contains(path)).cloned().collect();
+    let unmatched_audio = audios.iter().filter(|path| !used_audio.contains(path)).cloned().collect();
+
+    Ok(MatchPreview {
+      pairs,
+      unmatched_videos,
+      unmatched_audio,
+      notes: plan.notes,
+      manual_groups: plan.manual_groups,
+      tags: plan.tags,
+    })
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()))?
+}
+
+/// Videos and audio files whose runtimes all lie within tolerance of each
+/// other, so pairing them by duration would be a guess.
+#[derive(Debug, Clone, Serialize)]
+struct ManualGroup {
+  videos: Vec<String>,
+  audios: Vec<String>,
+}
+
+struct DurationMatch {
+  pairs: Vec<(String, String)>,
+  manual_groups: Vec<ManualGroup>,
+}
+
+/// Probed durations of `entries`; unprobeable files are noted and left out.
+fn entry_durations(
+  app: &AppHandle,
+  ffprobe: &Path,
+  entries: Vec<(String, String)>,
+  low_priority: Option<bool>,
+  notes: &mut Vec<String>,
+) -> Vec<(String, f64)> {
+  let durations = parallel_map(&entries, PROBE_WORKERS, |(_, path)| {
+    probe_cached(app, ffprobe, path, low_priority).ok().and_then(|probe| probe.duration)
+  });
+  entries
+    .into_iter()
+    .zip(durations)
+    .filter_map(|((name, path), duration)| match duration {
+      Some(duration) => Some((path, duration)),
+      None => {
+        notes.push(format!("Skipping {name}: duration unknown"));
+        None
+      }
+    })
+    .collect()
+}
+
+/// Pairs files by runtime. A pair is taken only when each file is the
+/// other's closest match and every runner-up is at least half the tolerance
+/// further off; repeated until nothing changes. Files still sharing
+/// candidates after that are returned as manual groups instead of guessed.
+fn match_duration_pairs(
+  app: &AppHandle,
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+  tolerance: f64,
+  low_priority: Option<bool>,
+  notes: &mut Vec<String>,
+) -> Result<DurationMatch, String> {
+  let ffprobe = resolve_ffprobe(app)?;
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, audio_extensions);
+  let videos = entry_durations(app, &ffprobe, videos, low_priority, notes);
+  let audios = entry_durations(app, &ffprobe, audios, low_priority, notes);
+  let margin = tolerance / 2.0;
+  let diff = |video: usize, audio: usize| (videos[video].1 - audios[audio].1).abs();
+
+  let mut video_taken = vec![false; videos.len()];
+  let mut audio_taken = vec![false; audios.len()];
+  let mut pairs = Vec::new();
+  loop {
+    // Closest free counterpart and the runner-up's distance, per side.
+    let best = |len: usize, taken: &[bool], distance: &dyn Fn(usize) -> f64| {
+      let mut candidates: Vec<(usize, f64)> = (0..len)
+        .filter(|&index| !taken[index])
+        .map(|index| (index, distance(index)))
+        .filter(|(_, distance)| *distance <= tolerance)
+        .collect();
+      candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+      candidates.first().map(|&(index, closest)| {
+        let clear = candidates.get(1).map_or(true, |&(_, next)| next - closest >= margin);
+        (index, clear)
+      })
+    };
+    let mut matched = Vec::new();
+    for video in (0..videos.len()).filter(|&video| !video_taken[video]) {
+      let Some((audio, video_clear)) = best(audios.len(), &audio_taken, &|audio| diff(video, audio)) else {
+        continue;
+      };
+      let Some((back, audio_clear)) = best(videos.len(), &video_taken, &|other| diff(other, audio)) else {
+        continue;
+      };
+      if back == video && video_clear && audio_clear {
+        matched.push((video, audio));
+      }
+    }
+    if matched.is_empty() {
+      break;
+    }
+    for (video, audio) in matched {
+      video_taken[video] = true;
+      audio_taken[audio] = true;
+      pairs.push((videos[video].0.clone(), audios[audio].0.clone()));
+    }
+  }
+
+  // Group what's left by shared candidates (connected components).
+  let mut manual_groups = Vec::new();
+  let mut seen_video = video_taken.clone();
+  let mut seen_audio = audio_taken.clone();
+  for start in 0..videos.len() {
+    if seen_video[start] {
+      continue;
+    }
+    seen_video[start] = true;
+    let (mut group_videos, mut group_audios) = (vec![start], Vec::new());
+    let mut pending = vec![start];
+    while let Some(video) = pending.pop() {
+      for (audio, audio_seen) in seen_audio.iter_mut().enumerate() {
+        if *audio_seen || diff(video, audio) > tolerance {
+          continue;
+        }
+        *audio_seen = true;
+        group_audios.push(audio);
+        for (other, video_seen) in seen_video.iter_mut().enumerate() {
+          if *video_seen || diff(other, audio) > tolerance {
+            continue;
+          }
+          *video_seen = true;
+          group_videos.push(other);
+          pending.push(other);
+        }
+      }
+    }
+    if group_audios.is_empty() {
+      notes.push(format!(
+        "No audio within {tolerance}s of {}",
+        file_display_name(Path::new(&videos[start].0))
+      ));
+      continue;
+    }
+    manual_groups.push(ManualGroup {
+      videos: group_videos.into_iter().map(|video| videos[video].0.clone()).collect(),
+      audios: group_audios.into_iter().map(|audio| audios[audio].0.clone()).collect(),
+    });
+  }
+  for group in &manual_groups {
+    notes.push(format!(
+      "{} videos and {} audio files have matching runtimes; pair them manually",
+      group.videos.len(),
+      group.audios.len()
+    ));
+  }
+  pairs.sort();
+  Ok(DurationMatch { pairs, manual_groups })
+}
+
+/// Release-name noise ignored when comparing names: codecs, sources and
+/// language tags that differ between video and audio releases.
+const FUZZY_NOISE_TOKENS: &[&str] = &[
+  "x264", "x265", "h264", "h265", "hevc", "avc", "aac", "ac3", "eac3", "dts", "flac", "web", "dl", "webrip",
+  "webdl", "bluray", "bdrip", "hdtv", "remux", "proper", "repack", "ger", "deu", "german", "eng", "english",
+  "dubbed", "dual", "264", "265",
+];
+
+/// A fuzzy-matched pair with its 0–100 confidence.
+#[derive(Debug, Clone, Serialize)]
+struct FuzzyPair {
+  video: String,
+  audio: String,
+  confidence: u8,
+}
+
+/// Lowercased title words of a file name, without the extension, episode
+/// markers, one- and two-digit numbers, resolutions, `FUZZY_NOISE_TOKENS` or
+/// a scene release group. Longer numbers stay, as they tell years and
+/// titles like "The 100" apart.
+fn name_tokens(name: &str) -> HashSet<String> {
+  static MARKER: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+  let marker = MARKER.get_or_init(|| Regex::new(r"^(s\d+(e\d+)*|e\d+|\d+x\d+|\d+p|\d{1,2})$").expect("marker pattern is valid"));
+  let noise = |token: &str| marker.is_match(token) || FUZZY_NOISE_TOKENS.contains(&token);
+  let stem = Path::new(name).file_stem().map(|stem| stem.to_string_lossy().to_lowercase()).unwrap_or_default();
+  // Scene names end in `-GROUP` right after a codec or source tag.
+  let stem = match stem.rsplit_once('-') {
+    Some((head, group))
+      if group.chars().all(char::is_alphanumeric)
+        && head.rsplit(|c: char| !c.is_alphanumeric()).next().is_some_and(noise) =>
+    {
+      head
+    }
+    _ => stem.as_str(),
+  };
+  stem
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|token| !token.is_empty() && !noise(token))
+    .map(str::to_string)
+    .collect()
+}
+
+/// Token-set similarity in `0.0..=1.0`: the mean of the Dice coefficient and
+/// the overlap with the smaller set, so an episode title present on only
+/// one side costs less than a different show name.
+fn token_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+  if a.is_empty() || b.is_empty() {
+    return 0.0;
+  }
+  let shared = a.intersection(b).count() as f64;
+  let dice = 2.0 * shared / (a.len() + b.len()) as f64;
+  let overlap = shared / a.len().min(b.len()) as f64;
+  (dice + overlap) / 2.0
+}
+
+/// Scores a video/audio name pair 0–100. Agreeing episode numbers carry half
+/// the score; conflicting ones rule the pair out. Without episode numbers on
+/// both sides the score rests on the names alone and tops out at 70.
+fn fuzzy_confidence(
+  video: &str,
+  video_tag: Option<&EpisodeTag>,
+  audio: &str,
+  audio_tag: Option<&EpisodeTag>,
+) -> Option<u8> {
+  let similarity = token_similarity(&name_tokens(video), &name_tokens(audio));
+  let score = match (video_tag, audio_tag) {
+    (Some(video_tag), Some(audio_tag)) => {
+      let same_season = match (video_tag.season, audio_tag.season) {
+        (Some(video_season), Some(audio_season)) => video_season == audio_season,
+        _ => true,
+      };
+      if !same_season || video_tag.episodes[0] != audio_tag.episodes[0] {
+        return None;
+      }
+      50.0 + 50.0 * similarity
+    }
+    _ => 70.0 * similarity,
+  };
+  Some(score.round().clamp(0.0, 100.0) as u8)
+}
+
+/// Pairs media files by best fuzzy score, greedily: the highest-scoring
+/// candidate pair wins and both files drop out. Ties keep natural order.
+fn match_fuzzy_pairs(
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+  match_pattern: Option<&str>,
+) -> Result<Vec<FuzzyPair>, String> {
+  let custom = compile_match_pattern(match_pattern)?;
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, audio_extensions);
+  let tags = |entries: &[(String, String)]| {
+    let bare_numbers = allows_bare_numbers(entries.iter().map(|(name, _)| name.as_str()));
+    entries
+      .iter()
+      .map(|(name, _)| parse_episode_with(name, custom.as_ref(), bare_numbers))
+      .collect::<Vec<_>>()
+  };
+  let (video_tags, audio_tags) = (tags(&videos), tags(&audios));
+
+  let mut candidates = Vec::new();
+  for (video_index, (video_name, _)) in videos.iter().enumerate() {
+    for (audio_index, (audio_name, _)) in audios.iter().enumerate() {
+      let (video_tag, audio_tag) = (video_tags[video_index].as_ref(), audio_tags[audio_index].as_ref());
+      if let Some(confidence) = fuzzy_confidence(video_name, video_tag, audio_name, audio_tag) {
+        if confidence > 0 {
+          candidates.push((confidence, video_index, audio_index));
+        }
+      }
+    }
+  }
+  candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+  let mut used_videos = HashSet::new();
+  let mut used_audio = HashSet::new();
+  let mut pairs = Vec::new();
+  for (confidence, video_index, audio_index) in candidates {
+    if used_videos.contains(&video_index) || used_audio.contains(&audio_index) {
+      continue;
+    }
+    used_videos.insert(video_index);
+    used_audio.insert(audio_index);
+    pairs.push((
+      video_index,
+      FuzzyPair {
+        video: videos[video_index].1.clone(),
+        audio: audios[audio_index].1.clone(),
+        confidence,
+      },
+    ));
+  }
+  pairs.sort_by_key(|(video_index, _)| *video_index);
+  Ok(pairs.into_iter().map(|(_, pair)| pair).collect())
+}
+
+/// Every video in `video_folder`, in natural order, with the audio the
+/// default series matching pairs it with.
+#[tauri::command]
+async fn preview_series_pairing(
+  video_folder: String,
+  audio_folder: String,
+  match_pattern: Option<String>,
+  audio_extensions: Option<Vec<String>>,
+) -> Result<Vec<(FileItem, Option<FileItem>)>, AppError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let (video_folder, audio_folder) = (Path::new(&video_folder), Path::new(&audio_folder));
+    let audio_extensions = audio_extensions.as_deref();
+    let pairs: HashMap<String, String> =
+      match_series_pairs(video_folder, audio_folder, audio_extensions, match_pattern.as_deref(), &mut Vec::new())
+        .map_err(AppError::InvalidRequest)?
+        .into_iter()
+        .collect();
+    Ok(
+      pairing_entries(video_folder, audio_folder, audio_extensions)
+        .0
+        .into_iter()
+        .map(|(name, path)| {
+          let audio = pairs.get(&path).map(|audio| {
+            let audio = Path::new(audio);
+            file_item(audio, file_display_name(audio), "audio")
+          });
+          (file_item(Path::new(&path), name, "video"), audio)
+        })
+        .collect(),
+    )
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()))?
+}
+
+/// Pairs series videos with audio files on the keys the bridge's
+/// `find_matching_files` uses, so the batch can be split before spawning:
+/// the first pattern giving both sides a common key wins, else every digit
+/// run in the name. Duplicate keys are noted like the other strategies'.
+fn match_series_pairs(
+  video_folder: &Path,
+  audio_folder: &Path,
+  audio_extensions: Option<&[String]>,
+  match_pattern: Option<&str>,
+  notes: &mut Vec<String>,
+) -> Result<Vec<(String, String)>, String> {
+  let (videos, audios) = pairing_entries(video_folder, audio_folder, audio_extensions);
+  if videos.is_empty() || audios.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let patterns = match match_pattern {
+    Some(pattern) if !pattern.is_empty() => {
+      vec![Regex::new(&nfc(pattern)).map_err(|err| format!("Invalid match pattern: {err}"))?]
+    }
+    _ => DEFAULT_MATCH_PATTERNS
+      .iter()
+      .filter_map(|pattern| Regex::new(pattern).ok())
+      .collect(),
+  };
+
+  // Each attempt notes into its own list; only the one used reports.
+  let keyed = |key: &dyn Fn(&str) -> Option<MatchKey>| {
+    let mut attempt = Vec::new();
+    let video_map = key_entries(videos.clone(), key, "match key", "no match key", &mut attempt);
+    let audio_map = key_entries(audios.clone(), key, "match key", "no match key", &mut attempt);
+    (video_map, audio_map, attempt)
+  };
+  let mut maps = None;
+  for pattern in &patterns {
+    let (video_map, audio_map, attempt) = keyed(&|name| capture_key(pattern, name));
+    if video_map.keys().any(|key| audio_map.contains_key(key)) {
+      maps = Some((video_map, audio_map, attempt));
+      break;
+    }
+  }
+  // Nothing lined up: fall back to every digit run in the name.
+  let (video_map, audio_map, attempt) = maps.unwrap_or_else(|| keyed(&|name| digit_key(digit_runs(), name)));
+  notes.extend(attempt);
+
+  let mut pairs: Vec<(String, String)> = video_map
+    .iter()
+    .filter_map(|(key, video)| audio_map.get(key).map(|audio| (video.clone(), audio.clone())))
+    .collect();
+  pairs.sort();
+  Ok(pairs)
+}
+
+fn folder_entries(folder: &Path) -> Vec<(String, String)> {
+  let Ok(entries) = fs::read_dir(folder) else {
+    return Vec::new();
+  };
+  entries
+    .flatten()
+    .filter(|entry| entry.path().is_file())
+    .map(|entry| {
+      (
+        nfc(&entry.file_name().to_string_lossy()),
+        entry.path().to_string_lossy().to_string(),
+      )
+    })
+    .collect()
+}
+
+fn capture_key(pattern: &Regex, name: &str) -> Option<MatchKey> {
+  pattern.captures(name).map(|caps| {
+    caps
+      .iter()
+      .skip(1)
+      .map(|group| group.map(|m| m.as_str().to_string()))
+      .collect()
+  })
+}
+
+fn digit_key(digits: &Regex, name: &str) -> Option<MatchKey> {
+  let key: MatchKey = digits
+    .find_iter(name)
+    .map(|m| Some(m.as_str().to_string()))
+    .collect();
+  if key.is_empty() {
+    None
+  } else {
+    Some(key)
+  }
+}
+
+/// Bridge protocol version this app speaks; the bridge reports its own in `hello`.
+const BRIDGE_PROTOCOL: u32 = 1;
+
+/// How to start the bridge: the bundled sidecar, or `bridge.py` under python
+/// during development.
+enum BridgeProgram {
+  Sidecar(PathBuf),
+  Python { python: PathBuf, bridge: PathBuf },
+}
+
+impl BridgeProgram {
+  fn locate(app: &AppHandle) -> Result<Self, AppError> {
+    // An explicit override beats the bundled sidecar, or it could never be
+    // used in a build that has one.
+    if let Some(bridge) = bridge_override() {
+      let python = find_python_exe().unwrap_or_else(|| PathBuf::from("python"));
+      return Ok(BridgeProgram::Python { python, bridge });
+    }
+    if let Some(path) = find_sidecar_path(app) {
+      return Ok(BridgeProgram::Sidecar(path));
+    }
+    let bridge = find_bridge_path().ok_or(AppError::SidecarNotFound)?;
+    let python = find_python_exe().unwrap_or_else(|| PathBuf::from("python"));
+    Ok(BridgeProgram::Python { python, bridge })
+  }
+
+  fn command(&self) -> Command {
+    match self {
+      BridgeProgram::Sidecar(path) => Command::new(path),
+      BridgeProgram::Python { python, bridge } => {
+        let mut cmd = Command::new(python);
+        cmd.arg(bridge);
+        cmd
+      }
+    }
+  }
+
+  /// The file the about dialog should name: the sidecar, or `bridge.py`.
+  fn path(&self) -> &Path {
+    match self {
+      BridgeProgram::Sidecar(path) => path,
+      BridgeProgram::Python { bridge, .. } => bridge,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SidecarInfo {
+  path: String,
+  version: String,
+  protocol: u32,
+  capabilities: Vec<String>,
+  algorithms: Vec<String>,
+  /// `true` when `bridge.py` runs under python instead of the bundled sidecar.
+  python_fallback: bool,
+}
+
+/// Runs the bridge with `--version` and checks it speaks `BRIDGE_PROTOCOL`.
+#[tauri::command]
+async fn check_sidecar(app: AppHandle) -> Result<SidecarInfo, AppError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let program = BridgeProgram::locate(&app)?;
+    bridge_hello(&program).map_err(AppError::ProbeFailed)
+  })
+  .await
+  .map_err(|err| AppError::Other(err.to_string()))?
+}
+
+/// Asks `program` for its handshake with `--version`, failing unless it
+/// speaks `BRIDGE_PROTOCOL`.
+fn bridge_hello(program: &BridgeProgram) -> Result<SidecarInfo, String> {
+  let path = program.path().to_string_lossy().to_string();
+  let output = program
+    .command()
+    .arg("--version")
+    .stdin(Stdio::null())
+    .output()
+    .map_err(|err| format!("Failed to start {path}: {err}"))?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let hello = stdout
+    .lines()
+    .find_map(|line| match serde_json::from_str::<BridgeMessage>(line.trim()) {
+      Ok(BridgeMessage::Hello { version, protocol, capabilities, algorithms }) => {
+        Some((version, protocol, capabilities, algorithms))
+      }
+      _ => None,
+    });
+  let Some((version, protocol, capabilities, algorithms)) = hello else {
+    return Err(format!(
+      "{path} did not report a version; it predates protocol {BRIDGE_PROTOCOL}. Rebuild the sidecar."
+    ));
+  };
+  let protocol = protocol.unwrap_or(0);
+  if protocol != BRIDGE_PROTOCOL {
+    return Err(format!(
+      "{path} (version {version}) speaks bridge protocol {protocol}, but this app expects {BRIDGE_PROTOCOL}. Rebuild the sidecar."
+    ));
+  }
+  Ok(SidecarInfo {
+    path,
+    version,
+    protocol,
+    capabilities,
+    algorithms,
+    python_fallback: matches!(program, BridgeProgram::Python { .. }),
+  })
+}
+
+/// Rejects a `SyncRequest.algorithm` the bridge doesn't advertise, before any
+/// work starts.
+fn check_algorithm(app: &AppHandle, algorithm: &str) -> Result<(), AppError> {
+  let program = BridgeProgram::locate(app)?;
+  let hello = bridge_hello(&program).map_err(AppError::ProbeFailed)?;
+  if hello.algorithms.is_empty() {
+    return Err(AppError::InvalidRequest(format!(
+      "Bridge {} predates algorithm selection. Rebuild the sidecar.",
+      hello.version
+    )));
+  }
+  if !hello.algorithms.iter().any(|name| name == algorithm) {
+    return Err(AppError::InvalidRequest(format!(
+      "Unknown algorithm {algorithm}; expected one of {}",
+      hello.algorithms.join(", ")
+    )));
+  }
+  Ok(())
+}
+
+/// Lets the bridge find the same ffmpeg the app resolved, including a
+/// user-configured one that isn't on PATH.
+fn share_ffmpeg(app: &AppHandle, command: &mut Command) {
+  if let Some(dir) = find_ffmpeg_tool(app, "ffmpeg").and_then(|path| path.parent().map(Path::to_path_buf)) {
+    let inherited = std::env::var_os("PATH").unwrap_or_default();
+    let dirs = std::iter::once(dir).chain(std::env::split_paths(&inherited));
+    if let Ok(path) = std::env::join_paths(dirs) {
+      command.env("PATH", path);
+    }
+  }
+}
+
+/// How long the self-test waits for each line from the bridge.
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+struct SelfTestStage {
+  name: String,
+  ok: bool,
+  detail: String,
+}
+
+/// Outcome of `run_self_test`: every stage attempted, in order, stopping at
+/// the first failure.
+#[derive(Debug, Clone, Serialize)]
+struct SelfTestReport {
+  ok: bool,
+  failed_stage: Option<String>,
+  stages: Vec<SelfTestStage>,
+  /// Delay the bridge measured on its synthetic pair.
+  delay_ms: Option<f64>,
+}
 
-  if candidate.exists() {
-    Some(candidate)
-  } else {
-    None
+impl SelfTestReport {
+  fn pass(&mut self, name: &str, detail: impl Into<String>) {
+    self.stages.push(SelfTestStage { name: name.to_string(), ok: true, detail: detail.into() });
   }
-}
 
-fn find_bridge_path() -> Option<PathBuf> {
-  let candidate = PathBuf::from("python/bridge.py");
-  if candidate.exists() {
-    Some(candidate)
-  } else {
-    None
+  fn fail(mut self, name: &str, detail: impl Into<String>) -> Self {
+    self.stages.push(SelfTestStage { name: name.to_string(), ok: false, detail: detail.into() });
+    self.ok = false;
+    self.failed_stage = Some(name.to_string());
+    self
   }
 }
 
+/// Runs the bridge on a synthetic pair it generates itself, checking the
+/// whole path a sync takes: locating and spawning the bridge, writing the
+/// request, the handshake, the analysis and the exit.
 #[tauri::command]
-fn cancel_sync(state: State<'_, SyncState>) -> Result<(), String> {
-  state.cancel.store(true, Ordering::SeqCst);
-  Ok(())
-}
-
-#[derive(Debug, Serialize)]
-struct MediaProbe {
-  has_audio: bool,
-  has_video: bool,
-  duration: Option<f64>,
+async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, AppError> {
+  tauri::async_runtime::spawn_blocking(move || self_test(&app))
+    .await
+    .map_err(|err| AppError::Other(err.to_string()))
 }
 
-#[tauri::command]
-fn probe_media(app: AppHandle, path: String) -> Result<MediaProbe, String> {
-  let ffprobe_exe = if cfg!(windows) { "ffprobe.exe" } else { "ffprobe" };
-  let ffprobe_path = app
-    .path()
-    .resolve(&format!("resources/ffmpeg/{}", ffprobe_exe), BaseDirectory::Resource)
-    .unwrap_or_else(|_| PathBuf::from(ffprobe_exe));
+fn self_test(app: &AppHandle) -> SelfTestReport {
+  let mut report = SelfTestReport { ok: true, failed_stage: None, stages: Vec::new(), delay_ms: None };
+  let program = match BridgeProgram::locate(app) {
+    Ok(program) => program,
+    Err(err) => return report.fail("locate", err.to_string()),
+  };
+  report.pass("locate", program.path().to_string_lossy());
 
-  let output = Command::new(ffprobe_path)
-    .args([
-      "-v",
-      "error",
-      "-show_entries",
-      "format=duration",
-      "-show_streams",
-      "-of",
-      "json",
-      &path,
-    ])
-    .output()
-    .map_err(|err| err.to_string())?;
+  let mut command = program.command();
+  share_ffmpeg(app, &mut command);
+  command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+  let mut child = match command.spawn() {
+    Ok(child) => child,
+    Err(err) => return report.fail("spawn", format!("Failed to start the bridge: {err}")),
+  };
+  report.pass("spawn", format!("pid {}", child.id()));
 
-  if !output.status.success() {
-    return Err("ffprobe failed".to_string());
+  let written = child
+    .stdin
+    .take()
+    .ok_or_else(|| "stdin unavailable".to_string())
+    .and_then(|mut stdin| writeln!(stdin, r#"{{"type":"selftest"}}"#).map_err(|err| err.to_string()));
+  if let Err(err) = written {
+    let _ = child.kill();
+    return report.fail("stdin", format!("Failed to send the request: {err}"));
   }
+  report.pass("stdin", "Request sent");
 
-  let value: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|err| err.to_string())?;
-  let streams = value.get("streams").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-  let mut has_audio = false;
-  let mut has_video = false;
-  for stream in streams {
-    if let Some(kind) = stream.get("codec_type").and_then(|v| v.as_str()) {
-      if kind == "audio" {
-        has_audio = true;
-      } else if kind == "video" {
-        has_video = true;
+  let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+    let _ = child.kill();
+    return report.fail("stdout", "The bridge's output is unavailable");
+  };
+  // Drained alongside stdout so a chatty bridge can't block on a full pipe.
+  let stderr = std::thread::spawn(move || {
+    let mut text = String::new();
+    let _ = std::io::Read::read_to_string(&mut stderr, &mut text);
+    text.trim().to_string()
+  });
+  let (tx, rx) = std::sync::mpsc::channel();
+  std::thread::spawn(move || {
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+      if tx.send(line).is_err() {
+        break;
       }
     }
-  }
-
-  let duration = value
-    .get("format")
-    .and_then(|v| v.get("duration"))
-    .and_then(|v| v.as_str())
-    .and_then(|v| v.parse::<f64>().ok());
-
-  Ok(MediaProbe {
-    has_audio,
-    has_video,
-    duration,
-  })
-}
+  });
+  let next_message = || loop {
+    let line = rx.recv_timeout(SELF_TEST_TIMEOUT).ok()?;
+    if let Ok(message) = serde_json::from_str::<BridgeMessage>(line.trim()) {
+      return Some(message);
+    }
+  };
+  let stderr_of = |mut child: std::process::Child| {
+    let _ = child.kill();
+    let _ = child.wait();
+    stderr.join().unwrap_or_default()
+  };
 
-#[tauri::command]
-fn open_output_folder(path: String) -> Result<(), String> {
-  let path = PathBuf::from(path);
-  if !path.exists() {
-    return Err("Path not found".to_string());
+  match next_message() {
+    Some(BridgeMessage::Hello { version, protocol, capabilities, .. }) => {
+      let protocol = protocol.unwrap_or(0);
+      if protocol != BRIDGE_PROTOCOL {
+        let _ = child.kill();
+        return report.fail(
+          "handshake",
+          format!("Bridge {version} speaks protocol {protocol}, but this app expects {BRIDGE_PROTOCOL}"),
+        );
+      }
+      if !capabilities.iter().any(|capability| capability == "selftest") {
+        let _ = child.kill();
+        return report.fail("handshake", format!("Bridge {version} predates the self-test. Rebuild the sidecar."));
+      }
+      report.pass("handshake", format!("Bridge {version}, protocol {protocol}"));
+    }
+    _ => {
+      let stderr = stderr_of(child);
+      return report.fail("handshake", format!("No hello from the bridge. {stderr}").trim().to_string());
+    }
   }
 
-  #[cfg(target_os = "windows")]
-  {
-    Command::new("explorer")
-      .arg("/select,")
-      .arg(path)
-      .spawn()
-      .map_err(|err| err.to_string())?;
+  loop {
+    match next_message() {
+      Some(BridgeMessage::SelfTest { ok: true, delay_ms, .. }) => {
+        report.delay_ms = delay_ms;
+        report.pass("analysis", format!("Measured {:.0} ms on the synthetic pair", delay_ms.unwrap_or_default()));
+        break;
+      }
+      Some(BridgeMessage::SelfTest { stage, message, .. }) => {
+        let _ = child.kill();
+        return report.fail("analysis", format!("{stage}: {}", message.unwrap_or_default()));
+      }
+      Some(_) => continue,
+      None => {
+        let stderr = stderr_of(child);
+        return report.fail("analysis", format!("The bridge stopped without a result. {stderr}").trim().to_string());
+      }
+    }
   }
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    let folder = path.parent().unwrap_or(Path::new("."));
-    Command::new("open")
-      .arg(folder)
-      .spawn()
-      .or_else(|_| Command::new("xdg-open").arg(folder).spawn())
-      .map_err(|err| err.to_string())?;
+  match child.wait() {
+    Ok(status) if status.success() => report.pass("exit", "Exited cleanly"),
+    Ok(status) => return report.fail("exit", format!("Bridge exited with {status}")),
+    Err(err) => return report.fail("exit", err.to_string()),
   }
-
-  Ok(())
+  report
 }
 
-fn run_bridge(
-  app: AppHandle,
-  request: SyncRequest,
+fn run_bridge_process(
+  app: &AppHandle,
+  request: &SyncRequest,
   cancel: Arc<AtomicBool>,
-) -> Result<Vec<SyncResult>, String> {
-  let payload = serde_json::to_string(&request).map_err(|err| err.to_string())?;
+  batch: Option<&BatchProgress>,
+) -> Result<Vec<SyncResult>, AppError> {
+  let payload = serde_json::to_string(request).map_err(|err| AppError::Other(err.to_string()))?;
 
-  let mut command = if let Some(sidecar_path) = find_sidecar_path(&app) {
-    let _ = app.emit(
-      "sync-log",
-      format!("Using sidecar: {}", sidecar_path.to_string_lossy()),
-    );
-    Command::new(sidecar_path)
-  } else {
-    let _ = app.emit(
-      "sync-log",
-      "Sidecar not found. Attempting python fallback.",
-    );
-    let bridge_path = find_bridge_path().ok_or_else(|| {
-      "bridge.py not found. Build the sidecar (audiosync-cli) and ensure it exists in src-tauri/bin."
-        .to_string()
-    })?;
-    let python_exe = find_python_exe().unwrap_or_else(|| PathBuf::from("python"));
-    let _ = app.emit(
-      "sync-log",
-      format!(
-        "Sidecar not found. Falling back to python: {}",
-        python_exe.to_string_lossy()
-      ),
-    );
-    let mut cmd = Command::new(python_exe);
-    cmd.arg(bridge_path);
-    cmd
+  let program = match BridgeProgram::locate(app) {
+    Ok(program) => program,
+    Err(err) => {
+      emit_log(app, request.job_id, "Neither the sidecar nor bridge.py was found.");
+      return Err(err);
+    }
   };
+  match &program {
+    BridgeProgram::Sidecar(path) => {
+      emit_log(app, request.job_id, format!("Using sidecar: {}", path.to_string_lossy()));
+    }
+    BridgeProgram::Python { python, bridge } => {
+      let reason = if bridge_override().is_some() { format!("{BRIDGE_ENV} is set") } else { "no sidecar found".to_string() };
+      emit_log(
+        app,
+        request.job_id,
+        format!("Running {} with python {} ({reason})", bridge.to_string_lossy(), python.to_string_lossy()),
+      );
+    }
+  }
+  let mut command = program.command();
+  let low_priority = wants_low_priority(request.low_priority);
+  if low_priority {
+    lower_priority(&mut command);
+  }
+  emit_log(app, request.job_id, if low_priority { "Bridge priority: below normal" } else { "Bridge priority: normal" });
+
+  share_ffmpeg(app, &mut command);
 
+  emit_sync_command(app, request.job_id, &command, &payload);
+  ProcessTree::isolate(&mut command);
   command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+  let spawned = Instant::now();
   let mut child = match command.spawn() {
     Ok(child) => child,
     Err(err) => {
-      let _ = app.emit("sync-log", format!("Failed to start process: {err}"));
-      return Err(err.to_string());
+      emit_log(app, request.job_id, format!("Failed to start process: {err}"));
+      emit_sync_error(app, request.job_id, "spawn", err.to_string(), None);
+      return Err(if err.kind() == std::io::ErrorKind::NotFound {
+        AppError::BridgeNotFound
+      } else {
+        AppError::Io(err.to_string())
+      });
     }
   };
 
-  if let Some(mut stdin) = child.stdin.take() {
-    stdin.write_all(payload.as_bytes()).map_err(|err| err.to_string())?;
-  }
+  let mut stdin = child.stdin.take().ok_or_else(|| AppError::Io("Failed to capture stdin".to_string()))?;
+  let stdout = child.stdout.take().ok_or_else(|| AppError::Io("Failed to capture stdout".to_string()))?;
+  let stderr = child.stderr.take().ok_or_else(|| AppError::Io("Failed to capture stderr".to_string()))?;
+  let tree = Arc::new(ProcessTree::attach(child, request.job_id));
+  let child = tree.child.clone();
+  request.beat();
 
-  let stdout = child.stdout.take().ok_or_else(|| "Failed to capture stdout".to_string())?;
-  let stderr = child.stderr.take().ok_or_else(|| "Failed to capture stderr".to_string())?;
+  // The request goes out as a single line; stdin then stays open so a cancel
+  // can be delivered as a follow-up control message.
+  stdin
+    .write_all(payload.as_bytes())
+    .and_then(|_| stdin.write_all(b"\n"))
+    .and_then(|_| stdin.flush())?;
 
-  let app_for_stderr = app.clone();
-  std::thread::spawn(move || {
-    let reader = BufReader::new(stderr);
-    for line in reader.lines().flatten() {
-      let _ = app_for_stderr.emit("sync-log", line);
-    }
-  });
+  let finished = Arc::new(AtomicBool::new(false));
+  let activity = Arc::new(Mutex::new(FileActivity { spawned: Some(spawned), ..FileActivity::default() }));
+  spawn_stderr_reader(app.clone(), request, stderr);
+  let own_clock = ProgressClock::new();
+  let clock = batch.map_or(&own_clock, |batch| &batch.clock);
+  let limits = WatchLimits {
+    grace: Duration::from_secs(request.cancel_grace_secs.unwrap_or(DEFAULT_CANCEL_GRACE_SECS)),
+    file_timeout: request.file_timeout_secs.map(Duration::from_secs),
+  };
+  let watcher = spawn_watcher(
+    app.clone(),
+    tree,
+    stdin,
+    cancel.clone(),
+    finished.clone(),
+    activity.clone(),
+    limits,
+  );
 
   let mut results: Vec<SyncResult> = Vec::new();
-  let reader = BufReader::new(stdout);
-  for line in reader.lines().flatten() {
-    if cancel.load(Ordering::SeqCst) {
-      let _ = app.emit("sync-log", "Sync canceled by user.");
-      let _ = child.kill();
-      return Err("Canceled".to_string());
-    }
+  let mut reader = BufReader::new(stdout);
+  let max_line = request.max_line_bytes.unwrap_or(DEFAULT_MAX_LINE_BYTES);
+  // For video durations in the drift estimate.
+  let ffprobe = resolve_ffprobe(app).ok();
+  // Sharded runs check the handshake of their first process only.
+  let mut capabilities_checked = batch.is_some_and(|batch| batch.capabilities_checked.swap(true, Ordering::SeqCst));
+  // File and error of the result that stopped a `fail_fast` run.
+  let mut failure = None;
+  loop {
+    let line = match read_bounded_line(&mut reader, max_line) {
+      Ok(Some(BoundedLine::Line(line))) => line,
+      Ok(Some(BoundedLine::TooLong(length))) => {
+        emit_sync_error(
+          app,
+          request.job_id,
+          "parse",
+          format!("Skipped a {length}-byte bridge message over the {max_line}-byte limit"),
+          None,
+        );
+        continue;
+      }
+      Ok(None) => break,
+      Err(err) => {
+        log::warn!("Failed to read bridge output: {err}");
+        break;
+      }
+    };
+    request.beat();
     let line = line.trim();
     if line.is_empty() {
       continue;
     }
     let message: Result<BridgeMessage, _> = serde_json::from_str(line);
+    if !capabilities_checked {
+      capabilities_checked = true;
+      let capabilities = match &message {
+        Ok(BridgeMessage::Hello { capabilities, .. }) => Some(capabilities.as_slice()),
+        _ => None,
+      };
+      warn_missing_capabilities(app, request, capabilities);
+    }
     match message {
+      Ok(BridgeMessage::Hello { version, protocol, .. }) => {
+        emit_log(app, request.job_id, format!("Bridge version {version}"));
+        if protocol != Some(BRIDGE_PROTOCOL) {
+          let _ = app.emit(
+            "sync-warning",
+            serde_json::json!({
+              "file": null,
+              "message": format!("The bridge speaks protocol {}, but this app expects {BRIDGE_PROTOCOL}. Rebuild the sidecar.", protocol.unwrap_or(0)),
+            }),
+          );
+        }
+      }
       Ok(BridgeMessage::Progress { processed, total, current }) => {
-        let _ = app.emit(
-          "sync-progress",
-          serde_json::json!({ "processed": processed, "total": total, "current": current }),
-        );
+        // Sharded runs report batch-wide progress from their results instead.
+        if batch.is_none() {
+          let _ = app.emit("sync-progress", request.tagged(clock.payload(processed, total, current.as_deref())));
+        }
       }
       Ok(BridgeMessage::FileStart { file }) => {
-        let _ = app.emit("sync-file-start", serde_json::json!({ "file": file }));
+        touch_file(&activity, &file);
+        let _ = app.emit("sync-file-start", request.tagged(serde_json::json!({ "file": file })));
       }
       Ok(BridgeMessage::FileEnd { file, elapsed_ms }) => {
+        clock.record(elapsed_ms);
+        if let Ok(mut activity) = activity.lock() {
+          activity.last_seen.remove(&file);
+        }
         let _ = app.emit(
           "sync-file-end",
-          serde_json::json!({ "file": file, "elapsed_ms": elapsed_ms }),
+          request.tagged(serde_json::json!({ "file": file, "elapsed_ms": elapsed_ms })),
         );
       }
       Ok(BridgeMessage::FileProgress { file, percent }) => {
+        touch_file(&activity, &file);
         let _ = app.emit(
           "sync-file-progress",
-          serde_json::json!({ "file": file, "percent": percent }),
+          request.tagged(serde_json::json!({ "file": file, "percent": percent })),
         );
       }
       Ok(BridgeMessage::Log { message }) => {
-        let _ = app.emit("sync-log", message);
+        emit_log(app, request.job_id, message);
       }
       Ok(BridgeMessage::Result {
         videoFile,
@@ -547,7 +7499,25 @@ fn run_bridge(
         endDelay,
         error,
         elapsed_ms,
+        video_path,
+        audio_path,
+        confidence,
+        segments_used,
+        sample_rate,
+        video_stream,
+        audio_stream,
+        delays,
+        gain_db,
+        input_lufs,
+        algorithm,
       }) => {
+        let match_confidence = request.confidence_of(&videoFile, &audioFile);
+        let source_label = request.result_source(&videoFile, &audioFile, audio_path.as_deref());
+        let output_path = request.output_path_for(&videoFile, audio_path.as_deref(), startDelay);
+        let (video_language, audio_language) = request.languages_of(&videoFile, audio_path.as_deref());
+        let planned = request.pair_of(&videoFile, audio_path.as_deref()).map(|(video, _)| video.as_str());
+        let offset_hint_seconds = planned.and_then(|video| request.offset_hint_for(video));
+        let downmix = planned.and_then(|video| request.downmix_for(video));
         let result = SyncResult {
           videoFile,
           audioFile,
@@ -555,43 +7525,812 @@ fn run_bridge(
           endDelay,
           error,
           elapsedMs: elapsed_ms,
+          correlation_confidence: confidence,
+          segments_used,
+          match_confidence,
+          sample_rate,
+          video_stream,
+          audio_stream,
+          video_language,
+          audio_language,
+          delays,
+          output_path,
+          drift_ms_per_min: None,
+          speed_factor: None,
+          speed_mismatch: None,
+          gain_db,
+          input_lufs,
+          attempts: None,
+          segment_secs: None,
+          algorithm,
+          offset_hint_seconds,
+          downmix,
+          status: None,
+          source_label,
+          video_path,
+          audio_path,
         };
+        let result = request.flag_confidence(with_drift(app, request, ffprobe.as_deref(), request.limit_offset(result)));
+        if let Some(drift) = request.excess_drift(&result) {
+          let _ = app.emit(
+            "sync-warning",
+            request.tagged(serde_json::json!({
+              "file": result.videoFile,
+              "message": match &result.speed_mismatch {
+                Some(mismatch) => format!(
+                  "{} drifts {drift:.2} ms per minute, like a {} conversion",
+                  result.videoFile,
+                  mismatch.describe()
+                ),
+                None => format!("{} drifts {drift:.2} ms per minute; check the frame rate", result.videoFile),
+              },
+            })),
+          );
+        }
+        if result.status == Some(ResultStatus::LowConfidence) {
+          let _ = app.emit(
+            "sync-warning",
+            request.tagged(serde_json::json!({
+              "file": result.videoFile,
+              "message": format!(
+                "Low confidence ({:.2}) for {}; check this result",
+                confidence.unwrap_or_default(),
+                result.videoFile
+              ),
+            })),
+          );
+        }
+        if let Some(batch) = batch {
+          let processed = batch.record();
+          let _ = app.emit(
+            "sync-progress",
+            request.tagged(batch.clock.payload(processed, batch.total, Some(&result.videoFile))),
+          );
+        }
         results.push(result.clone());
-        let _ = app.emit("sync-result", result);
+        let retried = batch.is_some_and(|batch| batch.retries) || request.retry_pair(&result).is_some();
+        if !retried {
+          let _ = app.emit("sync-result", request.tagged(&result));
+        }
+        if let (true, Some(error)) = (request.fail_fast, &result.error) {
+          failure = Some((result.videoFile.clone(), error.clone()));
+          break;
+        }
       }
       Ok(BridgeMessage::Done { results: final_results }) => {
-        results = final_results;
-        let _ = app.emit("sync-done", &results);
+        results = final_results
+          .into_iter()
+          .map(|result| request.flag_confidence(with_drift(app, request, ffprobe.as_deref(), request.limit_offset(result))))
+          .map(|result| {
+            let (video_language, audio_language) =
+              request.languages_of(&result.videoFile, result.audio_path.as_deref());
+            let planned = request
+              .pair_of(&result.videoFile, result.audio_path.as_deref())
+              .map(|(video, _)| video.as_str());
+            SyncResult {
+              video_language,
+              audio_language,
+              offset_hint_seconds: planned.and_then(|video| request.offset_hint_for(video)),
+              downmix: planned.and_then(|video| request.downmix_for(video)),
+              ..result
+            }
+          })
+          .map(|result| SyncResult {
+            match_confidence: request.confidence_of(&result.videoFile, &result.audioFile),
+            source_label: request.result_source(&result.videoFile, &result.audioFile, result.audio_path.as_deref()),
+            output_path: request.output_path_for(&result.videoFile, result.audio_path.as_deref(), result.startDelay),
+            ..result
+          })
+          .collect();
+        if batch.is_none() {
+          emit_sync_done(app, request, &results);
+        }
       }
+      // Only answers `run_self_test`, never a sync request.
+      Ok(BridgeMessage::SelfTest { .. }) => {}
       Err(err) => {
-        let _ = app.emit("sync-log", format!("Invalid bridge message: {err}"));
+        emit_log(app, request.job_id, format!("Invalid bridge message: {err}"));
+        emit_sync_error(app, request.job_id, "parse", format!("Invalid bridge message: {err}"), None);
       }
     }
   }
 
-  let status = child.wait().map_err(|err| err.to_string())?;
+  if let Some((file, _)) = &failure {
+    emit_log(app, request.job_id, format!("{file} failed; stopping the batch (fail_fast)."));
+    // The watcher winds the bridge down like a cancel; keep draining stdout
+    // meanwhile so the bridge can't block on a full pipe.
+    cancel.store(true, Ordering::SeqCst);
+    while let Ok(Some(_)) = read_bounded_line(&mut reader, max_line) {}
+  }
+  finished.store(true, Ordering::SeqCst);
+  let status = wait_for_child(&child).map_err(AppError::Io)?;
+  let _ = watcher.join();
+  if let Some((file, error)) = failure {
+    emit_sync_error(app, request.job_id, "fail_fast", error.clone(), Some(file.clone()));
+    return Err(AppError::Aborted { file, error, results });
+  }
+
+  let (timed_out, stalled_at_start) = activity
+    .lock()
+    .map(|mut activity| (std::mem::take(&mut activity.timed_out), activity.stalled_at_start))
+    .unwrap_or_default();
+  let mut covered = request.covered_pairs(&results);
+  for file in &timed_out {
+    let video_file = nfc(file);
+    let index = request.pair_index(&video_file, None, None, &covered);
+    covered.extend(index);
+    let (video_path, audio_path) = index.and_then(|index| request.pairs.as_ref()?.get(index).cloned()).unzip();
+    let audio_file = audio_path.as_deref().map(|audio| file_display_name(Path::new(audio))).unwrap_or_default();
+    let error = format!("Timed out after {}s without progress", request.file_timeout_secs.unwrap_or_default());
+    let result = SyncResult {
+      match_confidence: request.confidence_of(&video_file, &audio_file),
+      status: Some(ResultStatus::TimedOut),
+      source_label: request.result_source(&video_file, &audio_file, audio_path.as_deref()),
+      video_path,
+      audio_path,
+      ..SyncResult::failed(video_file.clone(), audio_file.clone(), error)
+    };
+    results.push(result.clone());
+    let _ = app.emit("sync-result", request.tagged(&result));
+  }
+
+  // Files that finished before the cancel are still valid, so hand them back
+  // instead of discarding the whole batch.
+  if cancel.load(Ordering::SeqCst) {
+    if batch.is_none() {
+      emit_sync_canceled(app, request, &results);
+    }
+    return Ok(results);
+  }
+  // The watchdog killed the bridge on purpose; that isn't a process failure.
+  if !timed_out.is_empty() {
+    return Ok(results);
+  }
+  if stalled_at_start {
+    return Err(AppError::Other("The bridge stalled before starting a file".to_string()));
+  }
   if !status.success() {
-    return Err("Sync process failed".to_string());
+    let message = match status.code() {
+      Some(code) => format!("Sync process exited with code {code}"),
+      None => "Sync process was terminated".to_string(),
+    };
+    emit_sync_error(app, request.job_id, "exit", message, None);
+    return Err(AppError::Other("Sync process failed".to_string()));
   }
 
   Ok(results)
 }
 
+/// Last activity per in-flight file, plus files the watchdog gave up on.
+#[derive(Default)]
+struct FileActivity {
+  last_seen: HashMap<String, Instant>,
+  timed_out: Vec<String>,
+  /// When the bridge was started, until its first `file_start`. It counts
+  /// against the file timeout too, so a bridge stuck before any file is caught.
+  spawned: Option<Instant>,
+  /// Set once `spawned` ran past the timeout.
+  stalled_at_start: bool,
+}
+
+/// Forwards the bridge's stderr to `sync-log`. Decode progress comes from
+/// the bridge's own `file_progress` messages, not from ffmpeg stats here.
+fn spawn_stderr_reader(app: AppHandle, request: &SyncRequest, stderr: std::process::ChildStderr) {
+  let (heartbeat, job_id) = (request.heartbeat.clone(), request.job_id);
+  std::thread::spawn(move || {
+    let reader = BufReader::new(stderr);
+    for line in reader.lines().map_while(Result::ok) {
+      if let Some(heartbeat) = &heartbeat {
+        heartbeat.beat();
+      }
+      emit_log(&app, job_id, line);
+    }
+  });
+}
+
+fn touch_file(activity: &Mutex<FileActivity>, file: &str) {
+  if let Ok(mut activity) = activity.lock() {
+    activity.spawned = None;
+    activity.last_seen.insert(file.to_string(), Instant::now());
+  }
+}
+
+struct WatchLimits {
+  grace: Duration,
+  file_timeout: Option<Duration>,
+}
+
+/// Supervises a running bridge process.
+///
+/// On cancel it asks the bridge to stop via a `{"type":"cancel"}` line on
+/// stdin so it can clean up and flush partial results, killing the process
+/// only if it is still running after the grace period. When a file goes
+/// quiet for longer than the per-file timeout, the process is killed and the
+/// file recorded as timed out.
+fn spawn_watcher(
+  app: AppHandle,
+  tree: Arc<ProcessTree>,
+  mut stdin: ChildStdin,
+  cancel: Arc<AtomicBool>,
+  finished: Arc<AtomicBool>,
+  activity: Arc<Mutex<FileActivity>>,
+  limits: WatchLimits,
+) -> std::thread::JoinHandle<()> {
+  std::thread::spawn(move || {
+    while !finished.load(Ordering::SeqCst) {
+      if let Some(timeout) = limits.file_timeout {
+        if stalled_before_start(&activity, timeout) {
+          emit_sync_error(
+            &app,
+            tree.job_id,
+            "timeout",
+            format!("The bridge started no file within {}s; stopping it.", timeout.as_secs()),
+            None,
+          );
+          tree.stop(&app);
+          return;
+        }
+        let stalled = collect_timed_out(&activity, timeout);
+        if !stalled.is_empty() {
+          for file in stalled {
+            emit_sync_error(
+              &app,
+              tree.job_id,
+              "timeout",
+              format!("No progress for {}s; stopping the bridge.", timeout.as_secs()),
+              Some(file),
+            );
+          }
+          tree.stop(&app);
+          return;
+        }
+      }
+
+      if !cancel.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(100));
+        continue;
+      }
+
+      emit_log(&app, tree.job_id, "Sync canceled by user.");
+      let _ = stdin
+        .write_all(b"{\"type\":\"cancel\"}\n")
+        .and_then(|_| stdin.flush());
+      let deadline = Instant::now() + limits.grace;
+      while Instant::now() < deadline {
+        if finished.load(Ordering::SeqCst) || child_exited(&tree.child) {
+          // The bridge is gone, but ffmpeg processes it started may not be.
+          if tree.alive() {
+            tree.stop(&app);
+          }
+          return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+      }
+      emit_log(&app, tree.job_id, "Bridge did not stop in time; terminating it and its processes.");
+      tree.stop(&app);
+      return;
+    }
+  })
+}
+
+/// Whether the bridge has gone `timeout` since its start without beginning a
+/// file, marking it `stalled_at_start` if so.
+fn stalled_before_start(activity: &Mutex<FileActivity>, timeout: Duration) -> bool {
+  let Ok(mut activity) = activity.lock() else {
+    return false;
+  };
+  if !activity.spawned.is_some_and(|spawned| spawned.elapsed() > timeout) {
+    return false;
+  }
+  activity.spawned = None;
+  activity.stalled_at_start = true;
+  true
+}
+
+/// Moves files idle for longer than `timeout` into `timed_out` and returns them.
+fn collect_timed_out(activity: &Mutex<FileActivity>, timeout: Duration) -> Vec<String> {
+  let Ok(mut activity) = activity.lock() else {
+    return Vec::new();
+  };
+  let stalled: Vec<String> = activity
+    .last_seen
+    .iter()
+    .filter(|(_, seen)| seen.elapsed() > timeout)
+    .map(|(file, _)| file.clone())
+    .collect();
+  for file in &stalled {
+    activity.last_seen.remove(file);
+  }
+  activity.timed_out.extend(stalled.iter().cloned());
+  stalled
+}
+
+fn child_exited(child: &Mutex<Child>) -> bool {
+  child
+    .lock()
+    .map(|mut child| matches!(child.try_wait(), Ok(Some(_))))
+    .unwrap_or(true)
+}
+
+/// Polls rather than blocking in `wait()` so the cancel watcher can still
+/// take the lock to kill the process.
+fn wait_for_child(child: &Mutex<Child>) -> Result<ExitStatus, String> {
+  loop {
+    {
+      let mut guard = child.lock().map_err(|err| err.to_string())?;
+      if let Some(status) = guard.try_wait().map_err(|err| err.to_string())? {
+        return Ok(status);
+      }
+    }
+    std::thread::sleep(Duration::from_millis(50));
+  }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .plugin(tauri_plugin_clipboard_manager::init())
     .plugin(tauri_plugin_dialog::init())
+    .setup(|app| {
+      LOW_PRIORITY.store(load_settings_file(app.handle()).session.low_priority, Ordering::SeqCst);
+      Ok(())
+    })
     .plugin(tauri_plugin_log::Builder::default().level(log::LevelFilter::Info).build())
     .invoke_handler(tauri::generate_handler![
       pick_video_files,
+      pick_video_folders,
       pick_audio_files,
+      register_dropped_paths,
+      ingest_video_paths,
+      ingest_audio_paths,
+      rescan_folder,
+      cancel_listing,
+      detect_sibling_audio,
+      list_recent_jobs,
+      rerun_job,
+      apply_sync,
+      validate_pattern,
+      check_sidecar,
+      results_to_clipboard,
+      run_self_test,
+      preview_series_pairing,
+      preview_matches,
       start_sync,
+      enqueue_sync,
+      list_jobs,
       cancel_sync,
       probe_media,
+      probe_media_batch,
+      validate_files,
+      clear_probe_cache,
+      get_ffmpeg_path,
+      set_ffmpeg_path,
       open_output_folder,
-      export_csv
+      open_log_folder,
+      get_last_log_path,
+      clear_recent_directories,
+      load_settings,
+      save_settings,
+      export_csv,
+      export_json,
+      start_watch,
+      stop_watch
     ])
     .manage(SyncState::new())
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .manage(ProbeCache::default())
+    .manage(ListingState::default())
+    .manage(WatchState::default())
+    .build(tauri::generate_context!())
+    .expect("error while running tauri application")
+    .run(|app, event| {
+      if let tauri::RunEvent::Exit = event {
+        if let Some(watcher) = app.state::<WatchState>().take() {
+          let _ = watcher.join();
+        }
+      }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A fresh folder under the temp dir holding empty files named `names`.
+  fn fixture_dir(label: &str, names: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("audiosync-{label}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for name in names {
+      fs::write(dir.join(name), b"").unwrap();
+    }
+    dir
+  }
+
+  #[test]
+  fn fuzzy_confidence_fixtures() {
+    // (video, audio, score); `None` rules the pair out.
+    let cases = [
+      // Release groups, codecs and language tags are noise.
+      ("Show.Name.S01E02.1080p.WEB-DL.x264-NTb.mkv", "Show Name - S01E02 [GER].flac", Some(100)),
+      ("Show.Name.S01E02.720p.HDTV.x264-KILLERS.mkv", "Show.Name.S01E02.German.DL.AC3.Dubbed-GROUP.mka", Some(100)),
+      ("Spider-Man.S01E01.mkv", "Spider Man S01E01.flac", Some(100)),
+      // A year on one side only costs less than two different years.
+      ("Doctor.Who.S01E01.mkv", "Doctor Who (2005) S01E01.flac", Some(95)),
+      ("Doctor.Who.2005.S01E01.mkv", "Doctor Who (1963) S01E01.flac", Some(83)),
+      // Numeric titles keep their number.
+      ("The.100.S01E01.mkv", "The 100 - S01E01.flac", Some(100)),
+      ("The.100.S01E01.mkv", "The Flash - S01E01.flac", Some(75)),
+      // Without episode numbers on both sides, names alone top out at 70.
+      ("Movie Name (2010).mkv", "Movie Name 2010.flac", Some(70)),
+      ("Show.S01E01.mkv", "Show.S01E02.flac", None),
+      ("Show.S01E01.mkv", "Show.S02E01.flac", None),
+    ];
+    for (video, audio, score) in cases {
+      let (video_tag, audio_tag) = (parse_episode(video, None), parse_episode(audio, None));
+      assert_eq!(fuzzy_confidence(video, video_tag.as_ref(), audio, audio_tag.as_ref()), score, "{video} / {audio}");
+    }
+  }
+
+  #[test]
+  fn fuzzy_matching_pairs_near_duplicates_by_name() {
+    let videos = fixture_dir(
+      "fuzzy-videos",
+      &["Doctor.Who.2005.S01E01.mkv", "Doctor.Who.1963.S01E01.mkv", "The.Office.US.S01E01.mkv"],
+    );
+    let audios = fixture_dir(
+      "fuzzy-audio",
+      &[
+        "Doctor Who (1963) S01E01.flac",
+        "Doctor Who (2005) S01E01.flac",
+        "The Office UK S01E01.flac",
+        "The Office US S01E01.flac",
+      ],
+    );
+    let pairs = match_fuzzy_pairs(&videos, &audios, None, None).unwrap();
+    let _ = (fs::remove_dir_all(&videos), fs::remove_dir_all(&audios));
+    let named: Vec<(String, String)> = pairs
+      .iter()
+      .map(|pair| (file_display_name(Path::new(&pair.video)), file_display_name(Path::new(&pair.audio))))
+      .collect();
+    let expected = [
+      ("Doctor.Who.1963.S01E01.mkv", "Doctor Who (1963) S01E01.flac"),
+      ("Doctor.Who.2005.S01E01.mkv", "Doctor Who (2005) S01E01.flac"),
+      ("The.Office.US.S01E01.mkv", "The Office US S01E01.flac"),
+    ];
+    assert_eq!(named, expected.map(|(video, audio)| (video.to_string(), audio.to_string())));
+  }
+
+  #[test]
+  fn bare_episode_numbers_only_count_in_untagged_folders() {
+    let entries = |names: &[&str]| names.iter().map(|name| (name.to_string(), format!("/media/{name}"))).collect();
+    let mut notes = Vec::new();
+    let pairs = pair_entries_by_episode(
+      entries(&["Show.S01E01.mkv", "Show.S01E02.mkv", "Show.Behind.the.Scenes.05.mkv"]),
+      entries(&["01.flac", "02.flac", "05.flac"]),
+      None,
+      &mut notes,
+    );
+    assert_eq!(
+      pairs,
+      [("Show.S01E01.mkv", "01.flac"), ("Show.S01E02.mkv", "02.flac")]
+        .map(|(video, audio)| (format!("/media/{video}"), format!("/media/{audio}")))
+    );
+    assert!(notes.contains(&"Skipping Show.Behind.the.Scenes.05.mkv: no season/episode marker".to_string()));
+    assert_eq!(parse_episode("Show.Behind.the.Scenes.05.mkv", None).map(|tag| tag.key()), Some((1, 5)));
+  }
+
+  fn listed(name: &str, size: Option<u64>, modified: Option<u64>) -> FileItem {
+    FileItem {
+      name: name.to_string(),
+      path: format!("/media/{name}"),
+      file_type: "video".to_string(),
+      size,
+      duration: None,
+      modified,
+      created: None,
+      subtitles: Vec::new(),
+    }
+  }
+
+  fn sorted_names(by: SortKey, order: SortOrder) -> Vec<String> {
+    let mut files = vec![
+      listed("S1E10.mkv", Some(300), Some(2)),
+      listed("S1E9.mkv", Some(100), Some(3)),
+      listed("s1e2.mkv", Some(300), None),
+      listed("S1E1.mkv", None, Some(1)),
+    ];
+    sort_files(&mut files, SortOptions { by, order });
+    files.into_iter().map(|file| file.name).collect()
+  }
+
+  #[test]
+  fn sort_files_by_each_key() {
+    assert_eq!(sorted_names(SortKey::Name, SortOrder::Asc), ["S1E1.mkv", "s1e2.mkv", "S1E9.mkv", "S1E10.mkv"]);
+    assert_eq!(sorted_names(SortKey::Name, SortOrder::Desc), ["S1E10.mkv", "S1E9.mkv", "s1e2.mkv", "S1E1.mkv"]);
+    // Unknown sizes and times sort first; ties fall back to the path, in
+    // ascending order either way.
+    assert_eq!(sorted_names(SortKey::Size, SortOrder::Asc), ["S1E1.mkv", "S1E9.mkv", "S1E10.mkv", "s1e2.mkv"]);
+    assert_eq!(sorted_names(SortKey::Size, SortOrder::Desc), ["S1E10.mkv", "s1e2.mkv", "S1E9.mkv", "S1E1.mkv"]);
+    assert_eq!(sorted_names(SortKey::Modified, SortOrder::Asc), ["s1e2.mkv", "S1E1.mkv", "S1E10.mkv", "S1E9.mkv"]);
+    assert_eq!(sorted_names(SortKey::Modified, SortOrder::Desc), ["S1E9.mkv", "S1E10.mkv", "S1E1.mkv", "s1e2.mkv"]);
+  }
+
+  #[test]
+  fn natural_cmp_orders_digit_runs_by_value() {
+    use CmpOrdering::{Equal, Greater, Less};
+    let cases = [
+      ("S1E9", "S1E10", Less),
+      ("E002", "E10", Less),
+      ("file0010", "file9", Greater),
+      // Equal values order by padding, so they never compare equal.
+      ("E1", "E01", Less),
+      ("E01", "E001", Less),
+      ("E01", "E01", Equal),
+      // Runs too long for an integer still compare by value.
+      ("x99999999999999999999999", "x100000000000000000000000", Less),
+      // Letters compare case-insensitively, then by case for a stable order.
+      ("apple", "Banana", Less),
+      ("Émission", "émission", Less),
+      ("émission 2", "Émission 10", Less),
+      // Other scripts compare by code point, after ASCII.
+      ("Ärger", "Zebra", Greater),
+      // Only ASCII digits are numbers; others compare as characters.
+      ("Folge １０", "Folge ２", Less),
+      ("٣", "10", Greater),
+    ];
+    for (a, b, expected) in cases {
+      assert_eq!(natural_cmp(a, b), expected, "{a} vs {b}");
+      assert_eq!(natural_cmp(b, a), expected.reverse(), "{b} vs {a}");
+    }
+  }
+
+  /// Splits RFC 4180 CSV text into records of fields.
+  fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let (mut records, mut record, mut field) = (Vec::new(), Vec::new(), String::new());
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+      match (quoted, c) {
+        (true, '"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+        (true, '"') => quoted = false,
+        (true, c) => field.push(c),
+        (false, '"') if field.is_empty() => quoted = true,
+        (false, ',') => record.push(std::mem::take(&mut field)),
+        (false, '\n') => {
+          record.push(std::mem::take(&mut field));
+          records.push(std::mem::take(&mut record));
+        }
+        (false, c) => field.push(c),
+      }
+    }
+    records
+  }
+
+  #[test]
+  fn csv_escape_round_trips() {
+    let rows = [
+      vec!["plain", "with, comma", "say \"hi\"", "\"quoted\""],
+      vec!["two\nlines", "crlf\r\nline", "", "trailing,"],
+      vec!["Ünïcödé", " padded ", "\"", ","],
+    ];
+    let mut csv = String::new();
+    for row in &rows {
+      csv.push_str(&row.iter().map(|field| csv_escape(field)).collect::<Vec<_>>().join(","));
+      csv.push('\n');
+    }
+    assert_eq!(parse_csv(&csv), rows.map(|row| row.into_iter().map(String::from).collect::<Vec<_>>()));
+    assert_eq!(csv_escape("plain"), "plain");
+    assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+  }
+
+  #[test]
+  fn read_bounded_line_skips_an_oversized_line_and_resyncs() {
+    let input = format!("{{\"type\":\"log\"}}\n{}\nshort\r\nlast", "x".repeat(40));
+    // A tiny buffer makes the long line arrive over several reads.
+    let mut reader = BufReader::with_capacity(8, std::io::Cursor::new(input));
+    let mut lines = Vec::new();
+    while let Some(line) = read_bounded_line(&mut reader, 16).unwrap() {
+      lines.push(match line {
+        BoundedLine::Line(text) => text,
+        BoundedLine::TooLong(length) => format!("too long: {length}"),
+      });
+    }
+    assert_eq!(lines, ["{\"type\":\"log\"}", "too long: 40", "short\r", "last"]);
+  }
+
+  #[test]
+  fn decomposed_names_pair_with_composed_ones() {
+    // "Café" spelled with a combining accent, as macOS lists it.
+    let decomposed = "Cafe\u{301}.S01E01.mkv";
+    let videos = fixture_dir("nfd-videos", &[decomposed]);
+    let audios = fixture_dir("nfd-audio", &["Café S01E01.flac"]);
+    let pairs = match_fuzzy_pairs(&videos, &audios, None, None).unwrap();
+    let name = file_display_name(&videos.join(decomposed));
+    let _ = (fs::remove_dir_all(&videos), fs::remove_dir_all(&audios));
+    assert_eq!(name, "Café.S01E01.mkv");
+    assert_eq!(nfc(decomposed), name);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].confidence, 100);
+    // The on-disk path keeps the decomposed bytes.
+    assert!(pairs[0].video.ends_with(decomposed));
+  }
+
+  #[test]
+  fn bridge_result_names_are_composed() {
+    let line = r#"{"type":"result","videoFile":"Cafe\u0301.S01E01.mkv","audioFile":"Cafe\u0301.flac","startDelay":12.5}"#;
+    match serde_json::from_str::<BridgeMessage>(line).unwrap() {
+      BridgeMessage::Result { videoFile, audioFile, .. } => {
+        assert_eq!((videoFile.as_str(), audioFile.as_str()), ("Café.S01E01.mkv", "Café.flac"));
+      }
+      _ => panic!("not a result"),
+    }
+  }
+
+  #[test]
+  fn anchor_positions_label_with_two_decimals_at_most() {
+    let labels: Vec<String> = [0.1, 0.5, 1.0 / 3.0, 0.125, 0.0, 1.0].into_iter().map(percent_label).collect();
+    assert_eq!(labels, ["10", "50", "33.33", "12.5", "0", "100"]);
+  }
+
+  #[test]
+  fn pairing_honours_audio_extensions() {
+    let folder = fixture_dir("pairing-extensions", &["Show.S01E01.mkv", "Show.S01E01.flac", "Show.S01E01.dts", "notes.txt"]);
+    let names = |entries: Vec<MediaEntry>| entries.into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+
+    let (videos, audios) = pairing_entries(&folder, &folder, None);
+    assert_eq!(names(videos), ["Show.S01E01.mkv"]);
+    assert_eq!(names(audios), ["Show.S01E01.dts", "Show.S01E01.flac"]);
+
+    let extensions = vec![".DTS".to_string()];
+    let (_, audios) = pairing_entries(&folder, &folder, Some(&extensions));
+    assert_eq!(names(audios), ["Show.S01E01.dts"]);
+    fs::remove_dir_all(folder).unwrap();
+  }
+
+  /// Whether `pid` is running; a zombie waiting on its reaper counts as gone.
+  #[cfg(unix)]
+  fn process_running(pid: libc::pid_t) -> bool {
+    // SAFETY: signal 0 only checks that the process exists.
+    let exists = unsafe { libc::kill(pid, 0) == 0 };
+    exists && !fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| stat.contains(") Z "))
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn killing_the_tree_stops_the_bridges_children() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../python");
+    let venv = root.join(".venv/bin/python");
+    let python = if venv.exists() { venv } else { PathBuf::from("python3") };
+    let mut command = Command::new(python);
+    command.arg(root.join("tree_kill_harness.py")).stdin(Stdio::piped()).stdout(Stdio::piped());
+    ProcessTree::isolate(&mut command);
+    let mut child = command.spawn().unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let tree = ProcessTree::attach(child, None);
+    writeln!(stdin, "{{}}").unwrap();
+
+    let grandchild = stdout
+      .lines()
+      .map_while(Result::ok)
+      .find_map(|line| match serde_json::from_str(&line) {
+        Ok(BridgeMessage::Log { message }) => message.rsplit(' ').next()?.parse::<libc::pid_t>().ok(),
+        _ => None,
+      })
+      .expect("the harness reports its grandchild's pid");
+    assert!(process_running(grandchild));
+
+    tree.kill();
+    let deadline = Instant::now() + TREE_EXIT_TIMEOUT;
+    while process_running(grandchild) && Instant::now() < deadline {
+      std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(!process_running(grandchild), "grandchild {grandchild} outlived the kill");
+  }
+
+  #[test]
+  fn network_paths_key_on_their_share() {
+    for path in [r"\\NAS\Media\Show\ep.mkv", r"\\?\UNC\nas\media\Show", "//nas/Media/Show/ep.mkv", r"\\nas\media"] {
+      assert_eq!(share_root(Path::new(path)), "//nas/media", "{path}");
+    }
+  }
+
+  #[test]
+  fn series_matching_reports_duplicate_keys() {
+    let videos = fixture_dir("series-dup-videos", &["Show.S01E01.mkv", "Show.S01E01.v2.mkv", "Show.S01E02.mkv"]);
+    let audios = fixture_dir("series-dup-audio", &["Show.S01E01.flac", "Show.S01E02.flac"]);
+    let mut notes = Vec::new();
+    let pairs = match_series_pairs(&videos, &audios, None, None, &mut notes).unwrap();
+    let names: Vec<(String, String)> = pairs
+      .iter()
+      .map(|(video, audio)| (file_display_name(Path::new(video)), file_display_name(Path::new(audio))))
+      .collect();
+    assert_eq!(
+      names,
+      [
+        ("Show.S01E01.mkv".to_string(), "Show.S01E01.flac".to_string()),
+        ("Show.S01E02.mkv".to_string(), "Show.S01E02.flac".to_string()),
+      ]
+    );
+    assert_eq!(notes, ["Skipping Show.S01E01.v2.mkv: Show.S01E01.mkv has the same match key"]);
+    fs::remove_dir_all(videos).unwrap();
+    fs::remove_dir_all(audios).unwrap();
+  }
+
+  #[test]
+  fn cached_results_go_stale_with_their_inputs() {
+    let dir = fixture_dir("result-cache", &["ep.mkv", "ep.flac", "ep.synced.mkv"]);
+    let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+    let touch = |name: &str, millis: u64| {
+      let file = fs::File::options().write(true).open(dir.join(name)).unwrap();
+      file.set_modified(UNIX_EPOCH + Duration::from_millis(millis)).unwrap();
+    };
+    touch("ep.mkv", 1_000_000);
+    touch("ep.flac", 2_000_000);
+    touch("ep.synced.mkv", 3_000_000);
+    let entry = CachedResult {
+      video: path("ep.mkv"),
+      audio: path("ep.flac"),
+      video_modified: 1_000_000,
+      audio_modified: 2_000_000,
+      segment_duration: 60.0,
+      result: SyncResult {
+        output_path: Some(path("ep.synced.mkv")),
+        ..SyncResult::unmatched("ep.mkv".to_string(), "ep.flac".to_string(), None)
+      },
+    };
+    assert!(entry.covers(&path("ep.mkv"), &path("ep.flac"), 60.0));
+    assert!(!entry.covers(&path("ep.mkv"), &path("ep.flac"), 90.0));
+    assert!(!entry.is_stale());
+    let output = dir.join("ep.synced.mkv");
+    assert!(output_is_current(&output, &path("ep.mkv"), &path("ep.flac")));
+
+    // An output older than an input was written from earlier files.
+    touch("ep.synced.mkv", 1_500_000);
+    assert!(!output_is_current(&output, &path("ep.mkv"), &path("ep.flac")));
+    fs::remove_file(&output).unwrap();
+    assert!(!output_is_current(&output, &path("ep.mkv"), &path("ep.flac")));
+    assert!(entry.covers(&path("ep.mkv"), &path("ep.flac"), 60.0));
+
+    touch("ep.flac", 4_000_000);
+    assert!(entry.is_stale());
+    touch("ep.flac", 2_000_000);
+    fs::remove_file(dir.join("ep.mkv")).unwrap();
+    assert!(entry.is_stale());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn same_named_folders_get_their_parents_in_the_label() {
+    let folders: Vec<PathBuf> = ["/media/Show A/Season 1", "/media/Show B/Season 1", "/media/Movies", "/"]
+      .into_iter()
+      .map(PathBuf::from)
+      .collect();
+    assert_eq!(folder_labels(&folders), ["Show A/Season 1", "Show B/Season 1", "Movies", "/"]);
+  }
+
+  #[test]
+  fn pal_speedup_is_flagged_from_start_and_end_delays() {
+    // 23.976 fps video against audio sped up to 25 fps: over the span
+    // between the windows the audio runs out 4.1% early.
+    let (duration, segment) = (2700.0, 60.0);
+    let span_ms = (duration - segment) * 1000.0;
+    let end = 120.0 + span_ms * (1.0 - (24000.0 / 1001.0) / 25.0);
+    let (drift_ms_per_min, speed_factor) = drift_of(120.0, end, duration, segment, &[]).unwrap();
+    assert!(drift_ms_per_min > 2000.0);
+    let speed_factor = speed_factor.unwrap();
+    let mismatch = speed_mismatch_of(speed_factor, Some(23.976), &[]).unwrap();
+    assert_eq!(mismatch.label, "23.976 → 25 fps");
+    assert!(!mismatch.verified);
+    assert_eq!(mismatch.describe(), "23.976 → 25 fps (unverified)");
+
+    // Anchors on a straight line verify it; a bend rules it out.
+    let anchors = |middle: f64| {
+      [(0.0, 120.0), (0.5, middle), (1.0, end)].map(|(position, ms)| AnchorDelay { position, delay_ms: Some(ms) })
+    };
+    assert!(speed_mismatch_of(speed_factor, Some(23.976), &anchors((120.0 + end) / 2.0)).unwrap().verified);
+    assert_eq!(speed_mismatch_of(speed_factor, Some(23.976), &anchors(end)), None);
+    // A plain offset drifts too little to match any conversion.
+    assert_eq!(speed_mismatch_of(1.0001, Some(23.976), &[]), None);
+  }
 }
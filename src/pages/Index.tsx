@@ -15,6 +15,8 @@ import {
   History,
   Trash2,
   RotateCcw,
+  Stethoscope,
+  Copy,
 } from "lucide-react";
 import { ThemeToggle } from "@/components/ThemeToggle";
 import { toast } from "sonner";
@@ -30,6 +32,7 @@ interface FileItem {
   path: string;
   type: "video" | "audio";
   size?: number;
+  subtitles?: string[];
 }
 
 interface BridgeResult {
@@ -38,6 +41,28 @@ interface BridgeResult {
   startDelay: number | null;
   endDelay: number | null;
   elapsedMs?: number | null;
+  status?: "skipped" | "unmatched" | "warning" | "planned" | "low_confidence" | "timed_out" | null;
+  sourceLabel?: string | null;
+  segmentsUsed?: number | null;
+  matchConfidence?: number | null;
+  correlationConfidence?: number | null;
+  sampleRate?: number | null;
+  videoStream?: number | null;
+  audioStream?: number | null;
+  videoLanguage?: string | null;
+  audioLanguage?: string | null;
+  gainDb?: number | null;
+  inputLufs?: number | null;
+  attempts?: number | null;
+  segmentSecs?: number | null;
+  offsetHintSeconds?: number | null;
+  downmix?: string | null;
+  algorithm?: string | null;
+  delays?: { position: number; delayMs: number | null }[];
+  outputPath?: string | null;
+  driftMsPerMin?: number | null;
+  speedFactor?: number | null;
+  speedMismatch?: { video_fps: number; audio_fps: number; label: string; verified: boolean } | null;
   error?: string | null;
 }
 
@@ -45,10 +70,35 @@ interface SyncResult extends BridgeResult {
   confidence: "high" | "medium" | "low";
 }
 
+interface AudioStreamInfo {
+  index: number;
+  language?: string | null;
+  codec?: string | null;
+  channels?: number | null;
+}
+
 interface MediaProbe {
   has_audio: boolean;
   has_video: boolean;
   duration?: number | null;
+  audio_streams?: AudioStreamInfo[];
+}
+
+// One line per audio track, e.g. "#2 jpn ac3 6ch", for badge tooltips.
+const describeAudioStreams = (probe: MediaProbe) =>
+  (probe.audio_streams ?? [])
+    .map((stream) =>
+      [`#${stream.index}`, stream.language, stream.codec, stream.channels ? `${stream.channels}ch` : null]
+        .filter(Boolean)
+        .join(" ")
+    )
+    .join("\n");
+
+interface SelfTestReport {
+  ok: boolean;
+  failed_stage?: string | null;
+  stages: { name: string; ok: boolean; detail: string }[];
+  delay_ms?: number | null;
 }
 
 interface HistoryEntry {
@@ -62,8 +112,53 @@ interface HistoryEntry {
 interface PickResponse {
   folder: string | null;
   files: FileItem[];
+  errors?: { path: string; message: string }[];
+}
+
+interface SessionSettings {
+  last_video_folder: string | null;
+  last_audio_folder: string | null;
+  segment_duration: number | null;
+  match_pattern: string | null;
+  low_priority?: boolean;
+  normalize?: boolean;
 }
 
+type AppError =
+  | {
+      kind:
+        | "sidecar_not_found"
+        | "bridge_not_found"
+        | "canceled"
+        | "probe_failed"
+        | "invalid_request"
+        | "io"
+        | "other";
+      message?: string;
+    }
+  | { kind: "aborted"; message: { file: string; error: string; results: BridgeResult[] } };
+
+const isAppError = (error: unknown): error is AppError =>
+  typeof error === "object" && error !== null && "kind" in error;
+
+const errorMessage = (error: unknown) => {
+  if (isAppError(error)) {
+    switch (error.kind) {
+      case "sidecar_not_found":
+        return "Sync engine not found. Build the sidecar or reinstall the app.";
+      case "bridge_not_found":
+        return "Could not start the sync engine; Python may be missing.";
+      case "canceled":
+        return "Canceled";
+      case "aborted":
+        return `Stopped after ${error.message.file} failed: ${error.message.error}`;
+      default:
+        return error.message ?? error.kind;
+    }
+  }
+  return error instanceof Error ? error.message : String(error);
+};
+
 export default function Index() {
   const isTauri = !!(window as unknown as { __TAURI_INTERNALS__?: object }).__TAURI_INTERNALS__;
   const [mode, setMode] = useState<SyncMode>("movie");
@@ -75,6 +170,9 @@ export default function Index() {
   const [showAllHistory, setShowAllHistory] = useState(false);
   const [resultFilter, setResultFilter] = useState<"all" | "high" | "medium" | "low">("all");
   const [segmentDuration, setSegmentDuration] = useState(600);
+  const [downmix, setDownmix] = useState("");
+  const [algorithm, setAlgorithm] = useState("");
+  const [algorithms, setAlgorithms] = useState<string[]>([]);
   const [matchPattern, setMatchPattern] = useState("S(\\d+)E(\\d+)");
   const [results, setResults] = useState<SyncResult[]>([]);
   const [progress, setProgress] = useState({ current: 0, total: 0, percent: 0 });
@@ -159,6 +257,48 @@ export default function Index() {
     setLastAudioFolder(savedAudio);
   }, []);
 
+  const settingsLoadedRef = useRef(!isTauri);
+  const [lowPriority, setLowPriority] = useState(false);
+  const [normalize, setNormalize] = useState(false);
+
+  useEffect(() => {
+    if (!isTauri) return;
+    invoke<{ algorithms: string[] }>("check_sidecar")
+      .then(info => setAlgorithms(info.algorithms))
+      .catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    if (!isTauri) return;
+    invoke<SessionSettings>("load_settings")
+      .then(settings => {
+        if (settings.last_video_folder) setLastVideoFolder(settings.last_video_folder);
+        if (settings.last_audio_folder) setLastAudioFolder(settings.last_audio_folder);
+        if (settings.segment_duration) setSegmentDuration(settings.segment_duration);
+        if (settings.match_pattern) setMatchPattern(settings.match_pattern);
+        setLowPriority(!!settings.low_priority);
+        setNormalize(!!settings.normalize);
+      })
+      .catch(() => {})
+      .finally(() => {
+        settingsLoadedRef.current = true;
+      });
+  }, []);
+
+  useEffect(() => {
+    // Don't overwrite stored settings with defaults before they've loaded.
+    if (!isTauri || !settingsLoadedRef.current) return;
+    const settings: SessionSettings = {
+      last_video_folder: videoFolder ?? lastVideoFolder,
+      last_audio_folder: audioFolder ?? lastAudioFolder,
+      segment_duration: segmentDuration,
+      match_pattern: matchPattern,
+      low_priority: lowPriority,
+      normalize,
+    };
+    invoke("save_settings", { settings }).catch(() => {});
+  }, [videoFolder, audioFolder, lastVideoFolder, lastAudioFolder, segmentDuration, matchPattern, lowPriority, normalize]);
+
   useEffect(() => {
     if (videoFolder) {
       localStorage.setItem("audiosync-last-video-folder", videoFolder);
@@ -209,12 +349,20 @@ export default function Index() {
 
   const pairingPreview = () => {
     if (mode === "movie") {
-      const audioName = audioFiles[0]?.name ?? "No audio selected";
-      return videoFiles.map(file => ({
-        video: file.name,
-        audio: audioName,
-        status: audioFiles.length > 0 ? "matched" : "missing",
-      }));
+      if (audioFiles.length === 0) {
+        return videoFiles.map(file => ({
+          video: file.name,
+          audio: "No audio selected",
+          status: "missing",
+        }));
+      }
+      return videoFiles.flatMap(file =>
+        audioFiles.map(audio => ({
+          video: file.name,
+          audio: audio.name,
+          status: "matched",
+        }))
+      );
     }
 
     const audioByKey = new Map<string, string>();
@@ -236,6 +384,21 @@ export default function Index() {
     });
   };
 
+  const runSelfTest = async () => {
+    const pending = toast.loading("Running self-test…");
+    try {
+      const report = await invoke<SelfTestReport>("run_self_test");
+      if (report.ok) {
+        toast.success("Self-test passed", { id: pending, description: report.stages.find(stage => stage.name === "analysis")?.detail });
+      } else {
+        const failed = report.stages.find(stage => !stage.ok);
+        toast.error(`Self-test failed at ${report.failed_stage}`, { id: pending, description: failed?.detail });
+      }
+    } catch (error) {
+      toast.error(errorMessage(error), { id: pending });
+    }
+  };
+
   const handleCopyText = async (text: string) => {
     try {
       await navigator.clipboard.writeText(text);
@@ -293,11 +456,8 @@ export default function Index() {
       }
     } else {
       if (mode === "movie") {
-        setAudioFiles([newFiles[0]]);
+        setAudioFiles(prev => [...prev, ...newFiles]);
         setAudioSource("file");
-        if (newFiles.length > 1) {
-          toast.info("Movie mode uses a single audio file. Using the first file.");
-        }
       } else {
         setAudioFiles(prev => [...prev, ...newFiles]);
         setAudioSource("folder");
@@ -317,14 +477,14 @@ export default function Index() {
     e.preventDefault();
   }, []);
 
-  const handleSelectFolder = async (type: "video" | "audio") => {
+  const handleSelectFolder = async (type: "video" | "audio", pickFiles = false) => {
     if (!isTauri) {
       toast.error("File picker is only available in the desktop app.");
       return;
     }
     try {
       if (type === "video") {
-        const response = await invoke<PickResponse>("pick_video_files", { mode });
+        const response = await invoke<PickResponse>("pick_video_files", { mode, pickFiles });
         const mapped = response.files.map((file, index) => ({
           ...file,
           id: `video-${Date.now()}-${index}`,
@@ -334,10 +494,13 @@ export default function Index() {
         mapped.forEach(runProbe);
         setSelectedVideoIds(new Set());
         setVideoFolder(response.folder);
-        setVideoSource(response.folder ? "folder" : null);
+        setVideoSource(pickFiles ? "files" : response.folder ? "folder" : null);
         if (response.files.length > 0) {
           toast.success(`Added ${response.files.length} video files`);
         }
+        if (response.errors?.length) {
+          toast.warning(`Skipped ${response.errors.length} non-video file${response.errors.length > 1 ? 's' : ''}`);
+        }
       } else {
         const response = await invoke<PickResponse>("pick_audio_files", { mode });
         const mapped = response.files.map((file, index) => ({
@@ -427,10 +590,12 @@ export default function Index() {
       mode,
       video_folder: derivedVideoFolder,
       audio_folder: mode === "series" ? derivedAudioFolder : null,
-      audio_file: mode === "movie" ? audioFiles[0]?.path : null,
+      audio_file: mode === "movie" ? audioFiles.map(file => file.path) : null,
       video_files: mode === "movie" && videoSource === "files" ? videoFiles.map(file => file.path) : null,
       segment_duration: segmentDuration,
       match_pattern: mode === "series" ? matchPattern : null,
+      downmix: downmix || null,
+      algorithm: algorithm || null,
     };
 
     try {
@@ -451,14 +616,31 @@ export default function Index() {
       };
       setHistory(prev => [entry, ...prev].slice(0, 20));
 
+      const lowConfidence = normalized.filter(r => r.status === "low_confidence").length;
+      const failed = normalized.filter(r => !r.status && r.error).length;
+      const timedOut = normalized.filter(r => r.status === "timed_out").length;
       toast.success(`Analysis complete! ${normalized.length} files processed.`, {
-        description: `${normalized.filter(r => r.confidence === 'high').length} high confidence matches`,
+        description: [
+          `${normalized.filter(r => r.confidence === 'high').length} high confidence matches`,
+          lowConfidence > 0 ? `${lowConfidence} low confidence` : null,
+          failed > 0 ? `${failed} failed` : null,
+          timedOut > 0 ? `${timedOut} timed out` : null,
+        ].filter(Boolean).join(", "),
       });
     } catch (error) {
-      setStatus("idle");
       processStartRef.current = null;
-      const message = error instanceof Error ? error.message : String(error);
-      if (message.toLowerCase().includes("canceled")) {
+      if (isAppError(error) && error.kind === "aborted") {
+        setResults(error.message.results.map(result => ({
+          ...result,
+          confidence: computeConfidence(result.startDelay, result.endDelay),
+        })));
+        setStatus("complete");
+        toast.error(errorMessage(error));
+        return;
+      }
+      setStatus("idle");
+      const message = errorMessage(error);
+      if (isAppError(error) ? error.kind === "canceled" : message.toLowerCase().includes("canceled")) {
         toast.info("Analysis canceled");
       } else {
         toast.error("Analysis failed. Check logs for details.");
@@ -536,7 +718,17 @@ export default function Index() {
       await invoke("open_output_folder", { path: savedPath });
       toast.success("Exported to CSV");
     } catch (error) {
-      toast.error("Export canceled or failed");
+      if (isAppError(error) && error.kind === "canceled") return;
+      toast.error(`Export failed: ${errorMessage(error)}`);
+    }
+  };
+
+  const copyResults = async (resultsToCopy: SyncResult[]) => {
+    try {
+      await invoke("results_to_clipboard", { results: resultsToCopy });
+      toast.success(`Copied ${resultsToCopy.length} results`);
+    } catch (error) {
+      toast.error(`Copy failed: ${errorMessage(error)}`);
     }
   };
 
@@ -576,15 +768,18 @@ export default function Index() {
     let unlistenFileEnd: (() => void) | undefined;
     let unlistenFileProgress: (() => void) | undefined;
     let unlistenLog: (() => void) | undefined;
+    let unlistenStalled: (() => void) | undefined;
 
     const setup = async () => {
       if (!(window as unknown as { __TAURI_INTERNALS__?: object }).__TAURI_INTERNALS__) {
         return;
       }
-      unlistenProgress = await listen<{ processed: number; total: number }>("sync-progress", (event) => {
+      unlistenProgress = await listen<{ processed: number; total: number; eta_secs?: number | null }>("sync-progress", (event) => {
         const percent = event.payload.total > 0 ? Math.round((event.payload.processed / event.payload.total) * 100) : 0;
         setProgress({ current: event.payload.processed, total: event.payload.total, percent });
-        if (processStartRef.current && event.payload.processed > 0) {
+        if (event.payload.eta_secs != null) {
+          setEta(formatEta(event.payload.eta_secs * 1000));
+        } else if (processStartRef.current && event.payload.processed > 0) {
           const elapsedMs = Date.now() - processStartRef.current;
           const avgMs = elapsedMs / event.payload.processed;
           const remaining = avgMs * (event.payload.total - event.payload.processed);
@@ -600,8 +795,9 @@ export default function Index() {
         ]);
       });
 
-      unlistenDone = await listen<BridgeResult[]>("sync-done", (event) => {
-        const normalized = event.payload.map(result => ({
+      unlistenDone = await listen<{ results: BridgeResult[]; summary: string }>("sync-done", (event) => {
+        setLogs(prev => [...prev, `Done: ${event.payload.summary}`].slice(-200));
+        const normalized = event.payload.results.map(result => ({
           ...result,
           confidence: computeConfidence(result.startDelay, result.endDelay),
         }));
@@ -630,8 +826,20 @@ export default function Index() {
         }
       });
 
-      unlistenLog = await listen<string>("sync-log", (event) => {
-        setLogs(prev => [...prev, event.payload].slice(-200));
+      unlistenLog = await listen<{ message: string; job_id?: number }>("sync-log", (event) => {
+        setLogs(prev => [...prev, event.payload.message].slice(-200));
+      });
+
+      unlistenStalled = await listen<{ stalled: boolean; seconds: number }>("sync-stalled", (event) => {
+        if (event.payload.stalled) {
+          toast.warning(`No word from the sync engine for ${event.payload.seconds}s`, {
+            id: "sync-stalled",
+            description: "It may be stuck on a slow drive or network share.",
+            duration: Infinity,
+          });
+        } else {
+          toast.dismiss("sync-stalled");
+        }
       });
     };
 
@@ -645,6 +853,7 @@ export default function Index() {
       unlistenFileEnd?.();
       unlistenFileProgress?.();
       unlistenLog?.();
+      unlistenStalled?.();
     };
   }, [computeConfidence]);
 
@@ -715,6 +924,13 @@ export default function Index() {
             {statusLabel}
           </span>
           <ThemeToggle />
+          <button
+            onClick={runSelfTest}
+            className="p-1.5 rounded transition-colors text-muted-foreground hover:text-foreground hover:bg-secondary"
+            title="Test the sync pipeline"
+          >
+            <Stethoscope className="w-4 h-4" />
+          </button>
           <button
             onClick={() => setShowHistory(!showHistory)}
             className={`p-1.5 rounded transition-colors ${
@@ -785,6 +1001,15 @@ export default function Index() {
                         <Trash2 className="w-3.5 h-3.5" />
                       </button>
                     )}
+                    {mode === "movie" && (
+                      <button
+                        onClick={() => handleSelectFolder("video", true)}
+                        className="text-[10px] text-primary hover:underline"
+                        title="Pick individual video files"
+                      >
+                        Files
+                      </button>
+                    )}
                     <button
                       onClick={() => handleSelectFolder("video")}
                       className="text-[10px] text-primary hover:underline"
@@ -825,8 +1050,10 @@ export default function Index() {
                         {probeByPath[file.path] && (
                           <span className={`text-[10px] px-1.5 py-0.5 rounded ${
                             probeByPath[file.path].has_video ? "bg-success/15 text-success" : "bg-destructive/15 text-destructive"
-                          }`}>
+                          }`} title={describeAudioStreams(probeByPath[file.path]) || undefined}>
                             {probeByPath[file.path].has_video ? "Video OK" : "No video stream"}
+                            {(probeByPath[file.path].audio_streams?.length ?? 0) > 1 &&
+                              ` · ${probeByPath[file.path].audio_streams?.length} audio tracks`}
                           </span>
                         )}
                         <button
@@ -930,8 +1157,10 @@ export default function Index() {
                         {probeByPath[file.path] && (
                           <span className={`text-[10px] px-1.5 py-0.5 rounded ${
                             probeByPath[file.path].has_audio ? "bg-success/15 text-success" : "bg-destructive/15 text-destructive"
-                          }`}>
+                          }`} title={describeAudioStreams(probeByPath[file.path]) || undefined}>
                             {probeByPath[file.path].has_audio ? "Audio OK" : "No audio stream"}
+                            {(probeByPath[file.path].audio_streams?.length ?? 0) > 1 &&
+                              ` · ${probeByPath[file.path].audio_streams?.length} audio tracks`}
                           </span>
                         )}
                         <button
@@ -1086,6 +1315,14 @@ export default function Index() {
                         </button>
                       ))}
                     </div>
+                    <button
+                      onClick={() => copyResults(results)}
+                      disabled={!isTauri || results.length === 0}
+                      className="flex items-center gap-1.5 text-[10px] text-muted-foreground hover:text-foreground transition-colors disabled:opacity-50"
+                    >
+                      <Copy className="w-3.5 h-3.5" />
+                      Copy
+                    </button>
                     <button 
                       onClick={() => exportResults(results)}
                       className="flex items-center gap-1.5 text-[10px] text-muted-foreground hover:text-foreground transition-colors"
@@ -1346,6 +1583,38 @@ export default function Index() {
                   className="w-full bg-input rounded px-3 py-2 text-sm text-foreground focus:outline-none focus:ring-1 focus:ring-primary"
                 />
               </div>
+              <div>
+                <label className="text-[10px] uppercase tracking-wider text-muted-foreground mb-1 block">
+                  Downmix
+                </label>
+                <select
+                  value={downmix}
+                  onChange={(e) => setDownmix(e.target.value)}
+                  className="w-full bg-input rounded px-3 py-2 text-sm text-foreground focus:outline-none focus:ring-1 focus:ring-primary"
+                >
+                  <option value="">Default</option>
+                  <option value="mono">Mono</option>
+                  <option value="stereo">Front left/right</option>
+                  <option value="center_only">Center channel only</option>
+                </select>
+              </div>
+              {algorithms.length > 1 && (
+                <div>
+                  <label className="text-[10px] uppercase tracking-wider text-muted-foreground mb-1 block">
+                    Algorithm
+                  </label>
+                  <select
+                    value={algorithm}
+                    onChange={(e) => setAlgorithm(e.target.value)}
+                    className="w-full bg-input rounded px-3 py-2 text-sm text-foreground focus:outline-none focus:ring-1 focus:ring-primary"
+                  >
+                    <option value="">Default</option>
+                    {algorithms.map(name => (
+                      <option key={name} value={name}>{name}</option>
+                    ))}
+                  </select>
+                </div>
+              )}
               {mode === "series" && (
                 <div>
                   <label className="text-[10px] uppercase tracking-wider text-muted-foreground mb-1 block">